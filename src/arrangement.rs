@@ -0,0 +1,368 @@
+//! Arrangements of lines.
+//!
+//! The arrangement of a set of lines is the subdivision of the plane into vertices, edges and
+//! faces by the lines. It is stored as a doubly connected edge list, where each edge is split into
+//! two half-edges going in opposite directions, each with the face on its left hand side.
+//!
+//! Faces of an arrangement of lines can be unbounded. To keep all faces finite the arrangement is
+//! cut off by a bounding box containing all intersections of the lines. The faces touching the
+//! bounding box are the unbounded faces, and are marked as such.
+
+use std::collections::HashMap;
+
+use crate::brep_2d::Region2D;
+use crate::geometry::{Line, EPSILON};
+use crate::winding;
+
+/// The arrangement of a set of lines.
+#[derive(Clone, Debug)]
+pub struct Arrangement {
+    // The lines of the arrangement, followed by the four lines of the bounding box.
+    lines: Vec<Line>,
+    vertices: Vec<na::Point2<f64>>,
+    half_edges: Vec<HalfEdge>,
+    faces: Vec<Face>,
+}
+
+/// A directed half of an edge of an arrangement.
+#[derive(Copy, Clone, Debug)]
+pub struct HalfEdge {
+    /// The vertex the half-edge starts at.
+    pub origin: usize,
+    /// The half-edge going in the opposite direction.
+    pub twin: usize,
+    /// The next half-edge around the face.
+    pub next: usize,
+    /// The previous half-edge around the face.
+    pub prev: usize,
+    /// The face on the left hand side of the half-edge, `None` outside the bounding box.
+    pub face: Option<usize>,
+    /// The line the half-edge lies on.
+    pub line: usize,
+}
+
+/// A face of an arrangement, which is always convex.
+#[derive(Copy, Clone, Debug)]
+pub struct Face {
+    /// One of the half-edges around the face.
+    pub half_edge: usize,
+    /// Whether the face is bounded, or cut off by the bounding box.
+    pub bounded: bool,
+}
+
+impl Arrangement {
+    pub fn new(mut lines: Vec<Line>) -> Self {
+        let n = lines.len();
+
+        let intersections: Vec<_> = (0..n)
+            .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+            .filter_map(|(i, j)| lines[i].intersection_point(&lines[j]))
+            .collect();
+
+        // The bounding box contains all intersections with some margin, and the origins of the
+        // lines such that parallel lines do not coincide with the box.
+        let extent_points = intersections.iter()
+            .copied()
+            .chain(lines.iter().map(|l| l.origin()));
+        let (min, max) = extent_points.fold(
+            (na::Point2::origin(), na::Point2::origin()),
+            |(min, max): (na::Point2<f64>, na::Point2<f64>), p| (min.inf(&p), max.sup(&p)),
+        );
+        let margin = 1.0 + (max - min).amax();
+        let min = min - na::Vector2::repeat(margin);
+        let max = max + na::Vector2::repeat(margin);
+
+        let corners = [
+            na::Point2::new(min.x, min.y),
+            na::Point2::new(min.x, max.y),
+            na::Point2::new(max.x, max.y),
+            na::Point2::new(max.x, min.y),
+        ];
+        lines.extend((0..4).map(|i| Line::from_two_points(corners[i], corners[(i + 1) % 4])));
+
+        let in_box = |p: &na::Point2<f64>| {
+            p.x >= min.x - EPSILON && p.x <= max.x + EPSILON && p.y >= min.y - EPSILON && p.y <= max.y + EPSILON
+        };
+
+        // All intersections of lines inside the bounding box, including the box itself.
+        let mut points = Vec::new();
+        let mut point_lines = Vec::new();
+        for i in 0..lines.len() {
+            for j in i + 1..lines.len() {
+                if let Some(p) = lines[i].intersection_point(&lines[j]) {
+                    if in_box(&p) {
+                        points.push(p);
+                        point_lines.push((i, j));
+                    }
+                }
+            }
+        }
+
+        let (vertices, ids) = winding::merge_points(&points);
+
+        let mut line_vertices = vec![Vec::new(); lines.len()];
+        for (k, (i, j)) in point_lines.into_iter().enumerate() {
+            line_vertices[i].push(ids[k]);
+            line_vertices[j].push(ids[k]);
+        }
+
+        // Split each line into edges between consecutive vertices.
+        let mut half_edges = Vec::new();
+        for (line, vs) in line_vertices.iter_mut().enumerate() {
+            let pos = |v: &usize| lines[line].project_point(vertices[*v]).pos;
+            vs.sort_by(|a, b| pos(a).total_cmp(&pos(b)));
+            vs.dedup();
+
+            for w in vs.windows(2) {
+                let e = half_edges.len();
+                half_edges.push(HalfEdge { origin: w[0], twin: e + 1, next: 0, prev: 0, face: None, line });
+                half_edges.push(HalfEdge { origin: w[1], twin: e, next: 0, prev: 0, face: None, line });
+            }
+        }
+
+        // Sort the outgoing half-edges of each vertex counterclockwise. The next half-edge after
+        // arriving at a vertex is the one just clockwise from the way back.
+        let mut outgoing = vec![Vec::new(); vertices.len()];
+        for (h, half_edge) in half_edges.iter().enumerate() {
+            outgoing[half_edge.origin].push(h);
+        }
+
+        let angle = |h: usize| {
+            let d = vertices[half_edges[half_edges[h].twin].origin] - vertices[half_edges[h].origin];
+            d.y.atan2(d.x)
+        };
+
+        let mut next = vec![0; half_edges.len()];
+        for out in &mut outgoing {
+            out.sort_by(|a, b| angle(*a).total_cmp(&angle(*b)));
+
+            for (k, h) in out.iter().enumerate() {
+                let prev = out[(k + out.len() - 1) % out.len()];
+                next[half_edges[*h].twin] = prev;
+            }
+        }
+
+        for (h, n) in next.into_iter().enumerate() {
+            half_edges[h].next = n;
+            half_edges[n].prev = h;
+        }
+
+        // Each cycle of half-edges is a face, except for the single clockwise cycle around the
+        // bounding box.
+        let mut faces = Vec::new();
+        let mut visited = vec![false; half_edges.len()];
+        for start in 0..half_edges.len() {
+            if visited[start] {
+                continue
+            }
+
+            let mut cycle = Vec::new();
+            let mut h = start;
+            while !visited[h] {
+                visited[h] = true;
+                cycle.push(h);
+                h = half_edges[h].next;
+            }
+
+            let area: f64 = cycle.iter()
+                .map(|h| {
+                    let a = vertices[half_edges[*h].origin];
+                    let b = vertices[half_edges[half_edges[*h].next].origin];
+                    a.coords.perp(&b.coords)
+                })
+                .sum();
+
+            if area < 0.0 {
+                continue
+            }
+
+            for h in &cycle {
+                half_edges[*h].face = Some(faces.len());
+            }
+
+            faces.push(Face {
+                half_edge: start,
+                bounded: cycle.iter().all(|h| half_edges[*h].line < n),
+            });
+        }
+
+        Self {
+            lines,
+            vertices,
+            half_edges,
+            faces,
+        }
+    }
+
+    /// The lines of the arrangement, followed by the four lines of the bounding box.
+    pub fn lines(&self) -> &[Line] {
+        &self.lines
+    }
+
+    pub fn vertices(&self) -> &[na::Point2<f64>] {
+        &self.vertices
+    }
+
+    pub fn half_edges(&self) -> &[HalfEdge] {
+        &self.half_edges
+    }
+
+    pub fn faces(&self) -> &[Face] {
+        &self.faces
+    }
+
+    /// The half-edges around a face, in counterclockwise order.
+    pub fn face_half_edges(&self, f: usize) -> Vec<usize> {
+        let start = self.faces[f].half_edge;
+        let mut half_edges = vec![start];
+        let mut h = self.half_edges[start].next;
+        while h != start {
+            half_edges.push(h);
+            h = self.half_edges[h].next;
+        }
+        half_edges
+    }
+
+    /// The vertices around a face, in counterclockwise order.
+    pub fn face_vertices(&self, f: usize) -> Vec<na::Point2<f64>> {
+        self.face_half_edges(f).into_iter()
+            .map(|h| self.vertices[self.half_edges[h].origin])
+            .collect()
+    }
+
+    /// The face containing a point, or `None` if the point is outside the bounding box.
+    ///
+    /// Points on an edge belong to one of the faces next to the edge.
+    pub fn face_at(&self, point: na::Point2<f64>) -> Option<usize> {
+        (0..self.faces.len()).find(|f| {
+            self.face_half_edges(*f).into_iter().all(|h| {
+                let a = self.vertices[self.half_edges[h].origin];
+                let b = self.vertices[self.half_edges[self.half_edges[h].next].origin];
+                (b - a).perp(&(point - a)) >= 0.0
+            })
+        })
+    }
+
+    /// The region covered by the union of a set of faces.
+    ///
+    /// Unbounded faces are cut off by the bounding box. Returns `None` if the union can not be
+    /// represented as a region, for example if two faces only touch at a vertex.
+    pub fn region(&self, faces: &[usize]) -> Option<Region2D> {
+        let mut lines = Vec::new();
+        let mut loops = Vec::new();
+        let mut line_map = HashMap::new();
+
+        for f in faces {
+            // Regions have their inside on the right, so the half-edges are walked backwards.
+            let mut face_loop: Vec<usize> = self.face_half_edges(*f).into_iter()
+                .rev()
+                .map(|h| {
+                    let half_edge = self.half_edges[h];
+                    let line = self.lines[half_edge.line];
+                    let a = self.vertices[half_edge.origin];
+                    let b = self.vertices[self.half_edges[half_edge.twin].origin];
+                    let flip = (b - a).dot(&line.dir()) > 0.0;
+
+                    *line_map.entry((half_edge.line, flip)).or_insert_with(|| {
+                        lines.push(if flip { line.flip() } else { line });
+                        lines.len() - 1
+                    })
+                })
+                .collect();
+
+            face_loop.dedup();
+            while face_loop.len() > 1 && face_loop[0] == face_loop[face_loop.len() - 1] {
+                face_loop.pop();
+            }
+
+            loops.push(face_loop);
+        }
+
+        if loops.is_empty() {
+            return Some(Region2D::empty())
+        }
+
+        winding::resolve(&lines, &loops, |w| w > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::line;
+
+    #[test]
+    fn arrangement_triangle() {
+        let arrangement = Arrangement::new(vec![
+            line(0.0, 0.0, 1.0, 0.0),
+            line(0.0, 0.0, 0.0, 1.0),
+            line(1.0, 0.0, 0.0, 1.0),
+        ]);
+
+        assert_eq!(arrangement.faces().len(), 7);
+        assert_eq!(arrangement.faces().iter().filter(|f| f.bounded).count(), 1);
+
+        let half_edges = arrangement.half_edges();
+        for (i, h) in half_edges.iter().enumerate() {
+            assert_eq!(half_edges[h.twin].twin, i);
+            assert_eq!(half_edges[h.next].prev, i);
+            assert_eq!(half_edges[h.next].origin, half_edges[h.twin].origin);
+        }
+
+        let f = arrangement.face_at(na::Point2::new(0.2, 0.2)).unwrap();
+        assert!(arrangement.faces()[f].bounded);
+        assert_eq!(arrangement.face_vertices(f).len(), 3);
+
+        let region = arrangement.region(&[f]).unwrap();
+        assert_eq!(region.edges().len(), 3);
+        assert!((region.area() - 0.5).abs() < 1e-9);
+        assert!(region.inside(na::Point2::new(0.2, 0.2)));
+
+        let outside = arrangement.face_at(na::Point2::new(2.0, 2.0)).unwrap();
+        assert!(!arrangement.faces()[outside].bounded);
+        assert!(arrangement.region(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn arrangement_grid_union() {
+        let mut lines = Vec::new();
+        for i in 0..3 {
+            lines.push(line(i as f64, 0.0, i as f64, 1.0));
+            lines.push(line(0.0, i as f64, 1.0, i as f64).flip());
+        }
+
+        let arrangement = Arrangement::new(lines);
+        assert_eq!(arrangement.faces().len(), 16);
+        assert_eq!(arrangement.vertices().len(), 9 + 4 * 3 + 4);
+
+        let cells: Vec<_> = arrangement.faces().iter()
+            .enumerate()
+            .filter(|(_, f)| f.bounded)
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(cells.len(), 4);
+
+        let square = arrangement.region(&cells).unwrap();
+        assert_eq!(square.edges().len(), 4);
+        assert!((square.area() - 4.0).abs() < 1e-9);
+
+        // Two cells touching at a corner can not be a region, as the edges of a region may not
+        // touch.
+        let diagonal = [
+            arrangement.face_at(na::Point2::new(0.5, 0.5)).unwrap(),
+            arrangement.face_at(na::Point2::new(1.5, 1.5)).unwrap(),
+        ];
+        assert!(arrangement.region(&diagonal).is_none());
+
+        // An L shape including a face that is cut off by the bounding box.
+        let l_shape = [
+            arrangement.face_at(na::Point2::new(0.5, 0.5)).unwrap(),
+            arrangement.face_at(na::Point2::new(1.5, 0.5)).unwrap(),
+            arrangement.face_at(na::Point2::new(0.5, -0.5)).unwrap(),
+        ];
+        let region = arrangement.region(&l_shape).unwrap();
+        assert!(region.area() > 2.0);
+        assert!(region.inside(na::Point2::new(0.5, -0.5)));
+        assert!(!region.inside(na::Point2::new(1.5, -0.5)));
+    }
+}
@@ -1,232 +1,360 @@
-const EPSILON: f64 = 1e-5;
+//! A dimension independent framework for boundary regions.
+//!
+//! A [`Region`] is made of [`Cell`]s, connected pieces of the region given by a closed boundary
+//! which is either filled or a hole. The boundary of a cell is made of sub-regions of one
+//! dimension lower, such as the end points of an interval or the edges of a polygon. Operations
+//! between regions are expressed in terms of how the sub-regions of one cell are split by, or lie
+//! inside, another cell.
+
+/// A sub-region split by the boundary of another cell.
+#[derive(Clone, Debug)]
+pub struct Split<R, V> {
+    pub orig: R,
+    pub new_outside: Vec<V>,
+    pub new_inside: Vec<V>,
+}
 
-#[derive(Copy, Clone)]
-struct Plane {
-    n: na::UnitVector3<f64>,
-    d: f64,
+/// The intersection of two cells.
+///
+/// Sub-regions crossing the boundary of the other cell are split, sub-regions lying completely
+/// inside the other cell are listed as inside. Sub-regions outside the other cell are not listed.
+#[derive(Clone, Debug)]
+pub struct Intersection<R, V> {
+    pub splits_c1: Vec<Split<R, V>>,
+    pub splits_c2: Vec<Split<R, V>>,
+    pub inside_c1: Vec<R>,
+    pub inside_c2: Vec<R>,
 }
 
-impl Plane {
-    pub fn from_point_normal(p0: na::Point3<f64>, n: na::UnitVector3<f64>) -> Self {
-        let d = -n.into_inner().dot(&p0.coords);
+impl<R, V> Intersection<R, V> {
+    pub fn new() -> Self {
         Self {
-            n,
-            d
+            splits_c1: Vec::new(),
+            splits_c2: Vec::new(),
+            inside_c1: Vec::new(),
+            inside_c2: Vec::new(),
         }
     }
 
-    pub fn from_point_two_vectors(p0: na::Point3<f64>, u: na::Vector3<f64>, w: na::Vector3<f64>) -> Option<Self> {
-        let normal = na::Unit::try_new(u.cross(&w), EPSILON)?;
-        Some(Self::from_point_normal(p0, normal))
+    pub fn is_empty(&self) -> bool {
+        self.splits_c1.is_empty() && self.splits_c2.is_empty()
+            && self.inside_c1.is_empty() && self.inside_c2.is_empty()
     }
 
-    pub fn from_three_points(p0: na::Point3<f64>, p1: na::Point3<f64>, p2: na::Point3<f64>) -> Option<Self> {
-        Self::from_point_two_vectors(p0, p1 - p0, p2 - p0)
+    /// Checks if the boundaries of the two cells cross each other.
+    pub fn crosses(&self) -> bool {
+        !self.splits_c1.is_empty() || !self.splits_c2.is_empty()
     }
+}
 
-    pub fn normal(&self) -> na::UnitVector3<f64> {
-        self.n
+impl<R, V> Default for Intersection<R, V> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-pub struct Region3 {
-    points: Vec<na::Point3<f64>>,
-    faces: Vec<Shell>,
-}
+pub type SubRegionSplit<C> = Split<<C as Cell>::SubRegionRef, <C as Cell>::SubRegion>;
+pub type CellIntersection<C> = Intersection<<C as Cell>::SubRegionRef, <C as Cell>::SubRegion>;
 
-pub struct Shell {
-    faces: Vec<Face3>,
-}
+/// A connected piece of a region, filled on the inside of its boundary.
+pub trait Cell: Sized {
+    type SubRegion;
+    type SubRegionRef: Copy + PartialEq;
+    type SubRegionIter: Iterator<Item = Self::SubRegionRef>;
 
-pub struct Face3 {
-    loops: Vec<Loop>,
-    plane: Plane,
-}
+    fn subregions(&self) -> Self::SubRegionIter;
 
-pub struct Region2 {
-    points: Vec<na::Point2<f64>>,
-    loops: Vec<Loop>, 
-}
+    fn subregion(&self, rref: Self::SubRegionRef) -> Self::SubRegion;
 
-pub struct Loop {
-    segments: Vec<[usize; 2]>,
-}
+    /// The intersection of the boundaries of two cells, `None` if the cells are disjoint.
+    fn intersection(&self, other: &Self) -> Option<CellIntersection<Self>>;
 
-pub struct Region1 {
-    points: Vec<f64>,
-    segments: Vec<(usize, usize)>,
-}
+    fn intersects(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
 
-pub struct Segment2 {
-    a: na::Point2<f64>,
-    b: na::Point2<f64>,
+    /// Checks if the other cell lies inside this cell.
+    fn contains(&self, other: &Self) -> bool {
+        match self.intersection(other) {
+            Some(x) => !x.crosses() && x.inside_c1.is_empty()
+                && x.inside_c2.len() == other.subregions().count(),
+            None => false,
+        }
+    }
 }
 
-pub struct Ray2 {
-    s: na::Point2<f64>,
-    d: na::Vector2<f64>,
+/// The new sub-regions created by editing a cell.
+#[derive(Clone, Debug)]
+pub struct SplitResult<R> {
+    pub new_outside: Vec<R>,
+    pub new_inside: Vec<R>,
 }
 
-impl Segment2 {
-    pub fn ray_intersection(&self, ray: &Ray2) -> Option<na::Point2<f64>> {
-        // https://stackoverflow.com/questions/563198/how-do-you-detect-where-two-line-segments-intersect/565282#565282
-        let d = self.b - self.a;
-        if ray.d.perp(&d) < EPSILON && (self.a - ray.s).perp(&d) < EPSILON {
-            // Colinear
-
-        } else if ray.d.perp(&d) < EPSILON {
-            // Parallel
-            None
-        } else {
+impl<R> SplitResult<R> {
+    pub fn new() -> Self {
+        Self {
+            new_outside: Vec::new(),
+            new_inside: Vec::new(),
         }
     }
 }
 
-impl Loop {
-    pub fn edges<'a>(&'a self) -> impl 'a + Iterator<Item = [usize; 2]> {
-        self.points.iter().copied()
+impl<R> Default for SplitResult<R> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-impl Region2 {
-    pub fn segments<'a>(&'a self) -> impl 'a + Iterator<Item = Segment2> {
-        self.loops.iter().flat_map(|l| l.edges()).map(|e| e.map(|i| self.points[i]))
-    }
+/// A cell whose boundary can be split into smaller sub-regions.
+pub trait CellEdit: Cell {
+    /// Replaces a sub-region by the pieces of a split.
+    ///
+    /// References to other sub-regions of the cell stay valid.
+    fn apply_split(&mut self, split: &SubRegionSplit<Self>) -> SplitResult<Self::SubRegionRef>;
 
-    pub fn contains(&self, point: na::Point2<f64>) -> bool {
-        let mut inside = false;
-        let ray = Ray2 {
-            s: point,
-            d: na::Vector2::x(),
-        };
-        for segment in self.segments() {
-            inside ^= segment.ray_intersection(&ray).is_some();
-        }
+    /// Applies the splits of this cell in an intersection where this cell is the first cell.
+    ///
+    /// Returns all the sub-regions inside the other cell, both new and old, and the new
+    /// sub-regions outside the other cell.
+    fn apply_intersection(&mut self, intersection: &CellIntersection<Self>) -> SplitResult<Self::SubRegionRef> {
+        let mut res = SplitResult::new();
+        res.new_inside.extend(intersection.inside_c1.iter().copied());
 
-        inside
-    }
-}
+        for split in &intersection.splits_c1 {
+            let r = self.apply_split(split);
+            res.new_inside.extend(r.new_inside);
+            res.new_outside.extend(r.new_outside);
+        }
 
-pub enum SegmentRef {
-    Orig {
-        loop: usize,
-        segment: usize,
-    },
-    Cut {
-        i: usize,
-        side: bool,
+        res
     }
 }
 
-pub struct SegmentCut {
-    loop: usize,
-    segment: usize,
-    cut_point: usize,
-}
+/// A cell of each of two regions and their intersection.
+pub type CellPairIntersection<R> = (<R as Region>::CellRef, <R as Region>::CellRef, CellIntersection<<R as Region>::Cell>);
 
-pub struct RegionIntersection2 {
-    intersection_points: Vec<na::Point2<f64>>,
-    segment_cuts_r1: Vec<SegmentCut>,
-    segment_cuts_r2: Vec<SegmentCut>,
-
-    segments_inside_r1: Vec<SegmentRef>,
-    segments_inside_r2: Vec<SegmentRef>,
+/// The intersection of two regions, given by the pairs of intersecting cells.
+pub struct RegionIntersection<R: Region> {
+    pub cells: Vec<CellPairIntersection<R>>,
 }
 
-impl RegionIntersection2 {
-    pub fn compute(r1: &Region1, r2: &Region2) -> Self {
-    }
-}
+/// A region made of cells.
+pub trait Region: Sized {
+    type Cell: Cell;
+    type CellRef: Copy + PartialEq;
+    type CellIter: Iterator<Item = Self::CellRef>;
 
-pub struct Split<R, V> {
-    orig: R,
-    new_outside: Vec<V>,
-    new_inside: Vec<V>,
-} 
-
-struct Intersection<R, V> {
-    splits_c1: Vec<CellSplit<R, V>>,
-    splits_c2: Vec<CellSplit<R, V>>,
-    inside_c1: Vec<R>,
-    inside_c2: Vec<R>,
-}
+    fn cells(&self) -> Self::CellIter;
 
-pub type CellSplit<R: Region> = Split<R::CellRef, R::Cell>;
-pub type RegionIntersection<R: Region> = Intersection<R::CellRef, R::Cell>;
+    fn cell(&self, cref: Self::CellRef) -> Self::Cell;
 
-pub type SubRegionSplit<C: Cell> = Split<C::SubRegionRef, C::SubRegion>;
-pub type CellIntersection<C: Cell> = Intersection<C::SubRegionRef, C::SubRegion>;
+    /// Checks if a cell is a hole, the region is then outside the cell.
+    fn cell_hole(&self, cref: Self::CellRef) -> bool;
 
-trait SubRegion {
-    type Surface;
-    type SurfaceIntersection;
+    fn intersects_cell(&self, c1: &Self::Cell) -> bool {
+        // The number of cells the boundary of `c1` lies inside, where holes count negatively.
+        let mut depth = 0;
 
-    pub fn 
-}
+        for cref in self.cells() {
+            let Some(x) = self.cell(cref).intersection(c1) else {
+                continue
+            };
 
-trait Cell {
-    type SubRegion: SubRegion;
-    type SubRegionRef;
+            if x.crosses() {
+                return true
+            }
 
-    fn subregion(&self, rref: SubRegionRef) -> SubRegion;
+            // A filled cell inside `c1`.
+            if !self.cell_hole(cref) && !x.inside_c1.is_empty() {
+                return true
+            }
 
-    fn contains(&self, other: &Cell) -> bool;
+            if !x.inside_c2.is_empty() {
+                depth += cell_sign(self, cref);
+            }
+        }
 
-    fn intersects(&self, other: &Cell) -> bool {
-        self.intersection(other).is_some()
+        depth > 0
     }
 
-    fn intersection(&self, other: &Cell) -> Option<CellIntersection<Self>>;
-}
-
-pub struct SplitResult<R> {
-    new_outside: Vec<R>,
-    new_inside: Vec<R>,
-} 
+    /// Checks if the other region lies inside this region.
+    ///
+    /// This is the case if the boundary of each cell of `other` is inside this region, and no
+    /// boundary of this region is inside `other`.
+    fn contains(&self, other: &Self) -> bool {
+        let cells = self.intersection(other).map_or(Vec::new(), |x| x.cells);
 
-trait CellEdit: Cell {
-    fn apply_split(&mut self, split: &SubRegionSplit<Self>) -> SplitResult<Self::SubRegionRef>;
+        if cells.iter().any(|(_, _, x)| x.crosses()) {
+            return false
+        }
 
-    /*
-    fn apply_intersection(&mut self, intersection: &CellIntersection<Self>) -> SplitResult<Self::SubRegionRef> {
-        let mut res = SplitResult::new();
+        let other_inside = other.cells().all(|c2| {
+            cells.iter()
+                .filter(|(_, r2, x)| *r2 == c2 && !x.inside_c2.is_empty())
+                .map(|(r1, _, _)| cell_sign(self, *r1))
+                .sum::<i32>() > 0
+        });
+
+        let self_outside = self.cells().all(|c1| {
+            cells.iter()
+                .filter(|(r1, _, x)| *r1 == c1 && !x.inside_c1.is_empty())
+                .map(|(_, r2, _)| cell_sign(other, *r2))
+                .sum::<i32>() <= 0
+        });
+
+        other_inside && self_outside
     }
-    */
-}
-
-trait Region {
-    type Cell: Cell;
 
-    type CellIter: Iterator<Item = CellRef>;
+    fn intersection(&self, other: &Self) -> Option<RegionIntersection<Self>> {
+        let other_cells: Vec<_> = other.cells().map(|c| (c, other.cell(c))).collect();
+        let mut cells = Vec::new();
 
-    fn cells(&self) -> CellIter;
+        for r1 in self.cells() {
+            let c1 = self.cell(r1);
+            for (r2, c2) in &other_cells {
+                if let Some(x) = c1.intersection(c2) {
+                    cells.push((r1, *r2, x));
+                }
+            }
+        }
 
-    fn cell(&self, cref: CellRef) -> &Cell;
+        if cells.is_empty() {
+            None
+        } else {
+            Some(RegionIntersection {
+                cells
+            })
+        }
+    }
+}
 
-    fn cell_hole(&self, cref: CellRef) -> bool;
+fn cell_sign<R: Region>(region: &R, cref: R::CellRef) -> i32 {
+    if region.cell_hole(cref) {
+        -1
+    } else {
+        1
+    }
+}
 
-    fn intersects_cell(&self, c1: &Self::Cell) -> bool {
-        for cref in self.cells() {
-            let c2 = self.cell(cref);
-            if !self.cell_hole(cref) && c2.contains(c1) {
-                return true
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{rect, rect_points};
+    use crate::brep_1d::*;
+    use crate::brep_2d::*;
+
+    fn hole(x1: f64, y1: f64, x2: f64, y2: f64) -> Vec<na::Point2<f64>> {
+        let mut r = rect_points(x1, y1, x2, y2);
+        r.reverse();
+        r
+    }
 
-            if c2.intersects(c1) {
-                return true
-            }
-        }
+    #[test]
+    fn region_1d() {
+        let r1 = Region1D::new(vec![
+            Point1D::new(0.0, true),
+            Point1D::new(2.0, false),
+            Point1D::new(4.0, true),
+            Point1D::new(6.0, false),
+        ]).unwrap();
+        let inner = Region1D::interval(4.5, 5.0).unwrap();
+        let crossing = Region1D::interval(1.0, 3.0).unwrap();
+        let between = Region1D::interval(2.5, 3.5).unwrap();
+
+        assert_eq!(Region::cells(&r1).count(), 2);
+        assert!(Region::contains(&r1, &inner));
+        assert!(!Region::contains(&r1, &crossing));
+        assert!(!Region::contains(&r1, &between));
+        assert!(!Region::contains(&inner, &r1));
+
+        assert!(r1.intersects_cell(&inner));
+        assert!(r1.intersects_cell(&crossing));
+        assert!(!r1.intersects_cell(&between));
+        assert!(r1.intersects_cell(&Region1D::interval(-1.0, 7.0).unwrap()));
+
+        let x = Region::intersection(&r1, &crossing).unwrap();
+        assert_eq!(x.cells.len(), 1);
+        assert_eq!(x.cells[0].2.inside_c1, vec![1]);
+        assert_eq!(x.cells[0].2.inside_c2, vec![0]);
     }
 
-    pub fn intersection(&self, other: &Self) -> Option<RegionIntersection<Self>> {
-        let mut intersection = Intersection::new();
+    #[test]
+    fn region_2d() {
+        let ring = Region2D::from_polygons(&[rect_points(0.0, 0.0, 6.0, 6.0), hole(2.0, 2.0, 4.0, 4.0)]).unwrap();
+        let cells: Vec<_> = ring.cells().collect();
+        assert_eq!(cells.len(), 2);
+        assert_eq!(cells.iter().filter(|c| ring.cell_hole(**c)).count(), 1);
+
+        let in_ring = rect(0.5, 0.5, 1.5, 1.5);
+        let in_hole = rect(2.5, 2.5, 3.5, 3.5);
+        let crossing = rect(1.0, 1.0, 3.0, 3.0);
+        let around = rect(-1.0, -1.0, 7.0, 7.0);
+        let outside = rect(8.0, 8.0, 9.0, 9.0);
+
+        assert!(ring.contains(&in_ring));
+        assert!(!ring.contains(&in_hole));
+        assert!(!ring.contains(&crossing));
+        assert!(!ring.contains(&around));
+        assert!(around.contains(&ring));
+        assert!(!in_ring.contains(&ring));
+
+        let cell = |r: &Region2D| r.cell(0);
+        assert!(ring.intersects_cell(&cell(&in_ring)));
+        assert!(!ring.intersects_cell(&cell(&in_hole)));
+        assert!(ring.intersects_cell(&cell(&crossing)));
+        assert!(ring.intersects_cell(&cell(&around)));
+        assert!(!ring.intersects_cell(&cell(&outside)));
+
+        assert!(ring.intersection(&outside).is_none());
+        let x = ring.intersection(&crossing).unwrap();
+        assert_eq!(x.cells.len(), 2);
+        assert_eq!(x.cells.iter().filter(|(_, _, x)| x.crosses()).count(), 1);
+    }
 
-        for cref in other.cells() 
+    #[test]
+    fn cell_edit_2d() {
+        let square = rect(0.0, 0.0, 2.0, 2.0);
+        let other = rect(1.0, 1.0, 3.0, 3.0);
+        let mut c1 = square.cell(0);
+        let c2 = other.cell(0);
+
+        let x = c1.intersection(&c2).unwrap();
+        assert_eq!(x.splits_c1.len(), 2);
+        assert_eq!(x.splits_c2.len(), 2);
+        assert!(x.inside_c1.is_empty());
+
+        let res = c1.apply_intersection(&x);
+        assert_eq!(res.new_inside.len(), 2);
+        assert_eq!(res.new_outside.len(), 2);
+        assert_eq!(c1.subregions().count(), 6);
+
+        // The pieces inside the other cell are the upper right corner of the square.
+        let length: f64 = res.new_inside.iter()
+            .map(|e| c1.subregion(*e).length())
+            .sum();
+        assert!((length - 2.0).abs() < 1e-9);
+    }
 
-        if intersection.is_empty() {
-            None
-        } else {
-            Some(intersection)
-        }
+    #[test]
+    fn cell_edit_1d() {
+        let mut cell = Region1D::interval(0.0, 2.0).unwrap();
+        let other = Region1D::interval(1.0, 3.0).unwrap();
+
+        // The end points of intervals are never split by an intersection.
+        let x = Cell::intersection(&cell, &other).unwrap();
+        let res = cell.apply_intersection(&x);
+        assert_eq!(res.new_inside, vec![1]);
+        assert!(res.new_outside.is_empty());
+
+        let split = Split {
+            orig: 1,
+            new_outside: Vec::new(),
+            new_inside: vec![Point1D::new(1.5, false)],
+        };
+        let res = cell.apply_split(&split);
+        assert_eq!(res.new_inside, vec![1]);
+        assert_eq!(cell.subregions().count(), 2);
+        assert_eq!(cell.subregion(1).pos, 1.5);
+        assert!(!cell.inside(1.75));
     }
 }
@@ -1,4 +1,5 @@
-use crate::geometry::{LinePoint, EPSILON};
+use crate::geometry::LinePoint;
+use crate::brep::*;
 
 pub type Point1D = LinePoint;
 
@@ -23,7 +24,6 @@ impl Region1D {
 
         // First direction must be positive
         let mut dir = false;
-        let mut pos = None;
 
         for p in &points {
             // Dirs must be different
@@ -32,7 +32,6 @@ impl Region1D {
             }
 
             dir = p.dir;
-            pos = Some(p.pos);
         }
 
         // Last direction must be negative
@@ -45,6 +44,53 @@ impl Region1D {
         })
     }
 
+    /// Creates a region consisting of a single interval.
+    pub fn interval(a: f64, b: f64) -> Option<Self> {
+        Self::new(vec![LinePoint::new(a, true), LinePoint::new(b, false)])
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// The same region with positions negated, as seen from a flipped line.
+    pub fn flip(&self) -> Self {
+        Self {
+            points: self.points.iter().rev().map(|p| LinePoint::new(-p.pos, !p.dir)).collect(),
+        }
+    }
+
+    /// Splits the region into the part inside another region and the part outside it.
+    pub fn split(&self, other: &Region1D) -> (Region1D, Region1D) {
+        let mut xs: Vec<f64> = self.points.iter().chain(&other.points).map(|p| p.pos).collect();
+        xs.sort_by(f64::total_cmp);
+        xs.dedup();
+
+        let mut inside = Vec::new();
+        let mut outside = Vec::new();
+
+        for w in xs.windows(2) {
+            let mid = (w[0] + w[1]) / 2.0;
+            let a = self.inside(mid);
+            let b = other.inside(mid);
+
+            for (points, filled) in [(&mut inside, a && b), (&mut outside, a && !b)] {
+                let open = points.last().is_some_and(|p: &LinePoint| p.dir);
+                if open != filled {
+                    points.push(LinePoint::new(w[0], filled));
+                }
+            }
+        }
+
+        for points in [&mut inside, &mut outside] {
+            if points.last().is_some_and(|p| p.dir) {
+                points.push(LinePoint::new(xs[xs.len() - 1], false));
+            }
+        }
+
+        (Self { points: inside }, Self { points: outside })
+    }
+
     pub fn cells(&self) -> impl Iterator<Item = [usize; 2]> {
         (0..self.points.len()).step_by(2).map(|i| [i, i+1])
     }
@@ -68,11 +114,11 @@ impl Region1D {
 
     pub fn intersection(&self, other: &Region1D) -> RegionIntersection1D {
         let r1_inside = self.points().iter().enumerate()
-            .filter(|(i, p)| other.inside(p.pos))
+            .filter(|(_, p)| other.inside(p.pos))
             .map(|p| p.0).collect();
 
         let r2_inside = other.points().iter().enumerate()
-            .filter(|(i, p)| self.inside(p.pos))
+            .filter(|(_, p)| self.inside(p.pos))
             .map(|p| p.0).collect();
 
         RegionIntersection1D {
@@ -86,3 +132,78 @@ pub struct RegionIntersection1D {
     pub r1_inside: Vec<usize>,
     pub r2_inside: Vec<usize>,
 }
+
+impl Region for Region1D {
+    type Cell = Region1D;
+    type CellRef = usize;
+    type CellIter = std::ops::Range<usize>;
+
+    fn cells(&self) -> Self::CellIter {
+        0..self.points.len() / 2
+    }
+
+    fn cell(&self, cref: usize) -> Region1D {
+        Self {
+            points: self.points[2 * cref..2 * cref + 2].to_vec(),
+        }
+    }
+
+    fn cell_hole(&self, _cref: usize) -> bool {
+        false
+    }
+
+    fn contains(&self, other: &Region1D) -> bool {
+        Region1D::contains(self, other)
+    }
+}
+
+/// A 1d region is a cell if it is a single interval, the sub-regions are its end points.
+impl Cell for Region1D {
+    type SubRegion = Point1D;
+    type SubRegionRef = usize;
+    type SubRegionIter = std::ops::Range<usize>;
+
+    fn subregions(&self) -> Self::SubRegionIter {
+        0..self.points.len()
+    }
+
+    fn subregion(&self, rref: usize) -> Point1D {
+        self.points[rref]
+    }
+
+    fn intersection(&self, other: &Region1D) -> Option<CellIntersection<Self>> {
+        let x = Region1D::intersection(self, other);
+        let mut intersection = Intersection::new();
+        intersection.inside_c1 = x.r1_inside;
+        intersection.inside_c2 = x.r2_inside;
+
+        if intersection.is_empty() {
+            None
+        } else {
+            Some(intersection)
+        }
+    }
+
+    fn contains(&self, other: &Region1D) -> bool {
+        Region1D::contains(self, other)
+    }
+}
+
+/// The end points of an interval can not be split into several points, so the first piece of a
+/// split, preferring pieces inside the other cell, replaces the point and any other pieces are
+/// ignored. The new point must keep the points of the interval in order.
+impl CellEdit for Region1D {
+    fn apply_split(&mut self, split: &SubRegionSplit<Self>) -> SplitResult<usize> {
+        let mut res = SplitResult::new();
+
+        if let Some(p) = split.new_inside.first() {
+            self.points[split.orig] = *p;
+            res.new_inside.push(split.orig);
+        } else if let Some(p) = split.new_outside.first() {
+            self.points[split.orig] = *p;
+            res.new_outside.push(split.orig);
+        }
+
+        res
+    }
+}
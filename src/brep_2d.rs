@@ -1,5 +1,7 @@
 use crate::geometry::*;
 use crate::brep_1d::*;
+use crate::winding;
+use crate::brep::*;
 
 /// A 2d boundary region.
 ///
@@ -9,6 +11,7 @@ use crate::brep_1d::*;
 /// - Each edge has exactly two (distinct) neighbours (checked by structure)
 /// - No edges intersect
 /// - Each edge forms a proper 1d region
+/// - The inside of the region is on the inside of each edge line (the side the normal points to)
 #[derive(Clone, Debug)]
 pub struct Region2D {
     lines: Vec<Line>,
@@ -27,6 +30,119 @@ impl Region2D {
         Some(region)
     }
 
+    pub fn empty() -> Self {
+        Self {
+            lines: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    /// Creates a region from closed loops of lines.
+    ///
+    /// Each loop is a list of line indices, where each line is followed by the next line in the
+    /// loop. The inside of the region must be on the inside of each line. Consecutive lines in a
+    /// loop that are coincident become a single edge.
+    pub fn from_loops(lines: Vec<Line>, loops: &[Vec<usize>]) -> Option<Self> {
+        let mut edges = Vec::new();
+
+        for l in loops {
+            let mut l = l.clone();
+            l.dedup_by(|a, b| lines[*a].coincident(&lines[*b]));
+            while l.len() > 1 && lines[l[0]].coincident(&lines[l[l.len() - 1]]) {
+                l.pop();
+            }
+
+            let s = edges.len();
+            let n = l.len();
+            for (i, line) in l.iter().enumerate() {
+                edges.push(Edge::new(*line, s + (i + n - 1) % n, s + (i + 1) % n));
+            }
+        }
+
+        Self::new(lines, edges)
+    }
+
+    /// Creates a region from closed polygons.
+    ///
+    /// The inside of the region must be on the right hand side of each polygon, so outer
+    /// boundaries are clockwise and holes are counterclockwise.
+    pub fn from_polygons(polygons: &[Vec<na::Point2<f64>>]) -> Option<Self> {
+        let mut lines = Vec::new();
+        let mut loops = Vec::new();
+
+        for polygon in polygons {
+            let n = polygon.len();
+            loops.push((lines.len()..lines.len() + n).collect());
+            for i in 0..n {
+                lines.push(Line::from_two_points(polygon[i], polygon[(i + 1) % n]));
+            }
+        }
+
+        Self::from_loops(lines, &loops)
+    }
+
+    /// Simplifies the representation of the region.
+    ///
+    /// Coincident lines are merged into one line, consecutive edges on the same line are merged
+    /// into one edge and lines that are not used by any edge are removed. Lines are only merged
+    /// if they have the same direction, as the direction of a line gives the side of the inside.
+    pub fn simplify(&self) -> Option<Self> {
+        Some(self.simplify_sourced()?.0)
+    }
+
+    // Simplifies the region, see `simplify`, and returns the edge of this region each edge of
+    // the result comes from. Merged edges come from their first edge in the order of the loop.
+    pub(crate) fn simplify_sourced(&self) -> Option<(Self, Vec<usize>)> {
+        let mut lines: Vec<Line> = Vec::new();
+        let mut line_map = vec![None; self.lines.len()];
+        let mut loops = Vec::new();
+        let mut sources = Vec::new();
+
+        for edge_loop in self.loops() {
+            let mut l = Vec::new();
+
+            for e in edge_loop {
+                let line = self.lines[self.edges[e].line];
+                let i = *line_map[self.edges[e].line].get_or_insert_with(|| {
+                    lines.iter().position(|l| l.coincident(&line)).unwrap_or_else(|| {
+                        lines.push(line);
+                        lines.len() - 1
+                    })
+                });
+                l.push((i, e));
+            }
+
+            // The same merging of consecutive edges as `from_loops`, which then keeps all edges.
+            l.dedup_by_key(|x| x.0);
+            while l.len() > 1 && l[0].0 == l[l.len() - 1].0 {
+                l.pop();
+            }
+
+            sources.extend(l.iter().map(|x| x.1));
+            loops.push(l.into_iter().map(|x| x.0).collect());
+        }
+
+        Some((Self::from_loops(lines, &loops)?, sources))
+    }
+
+    /// Transforms the region by an affine transformation, see [`Line::transform`].
+    ///
+    /// Fails with [`TransformError::Invalid`] if the transformed region is not valid. A
+    /// transformation that is not degenerate only causes this through rounding, for example when
+    /// the region is moved so far from the origin that its vertices are rounded onto each other.
+    pub fn transform<T: Into<na::Matrix3<f64>>>(&self, t: T) -> Result<Self, TransformError> {
+        let t = affine(t)?;
+        let lines = self.lines.iter()
+            .map(|l| l.transform(t))
+            .collect::<Result<_, _>>()?;
+
+        Self::new(lines, self.edges.clone()).ok_or(TransformError::Invalid)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
     fn check(&self) -> Option<()> {
         for e in 0..self.edges.len() {
             let _ = self.edge_region_safe(e)?;
@@ -41,14 +157,13 @@ impl Region2D {
 
     // Checks if a edge is a proper 1d region.
     //
-    // Also hecks that a edge is a proper node in a simple graph. Eg. siblings are also sibling of
-    // this node.
-    // Also checks that all other edges do not intersect with this region.
-    // nodes intersects with it.
+    // Also checks that a edge is a proper node in a simple graph. Eg. siblings are also sibling of
+    // this node, and the edge after this one has this edge as the edge before it.
+    // Also checks that no other edges intersect with this edge and that the edge has the inside
+    // of the region on the correct side.
     fn check_edge(&self, e: usize) -> Option<()> {
         let edge = self.edges[e];
         let line = self.lines[edge.line];
-        let r = self.edge_region(e);
 
         // Check that graph is valid.
         if e != self.edges[edge.x1].x1 && e != self.edges[edge.x1].x2 {
@@ -59,14 +174,36 @@ impl Region2D {
             return None
         }
 
-        let cut_region = self.cut_region_filter(line, 
-            |i| i != e && i != edge.x1 && i != edge.x2)?;
+        if self.edge_prev(self.edge_next(e)) != e {
+            return None
+        }
+
+        let (a, b) = self.edge_ends(e)?;
 
-        let inside = self.inside_edge(e)?;
+        // Compute the cut of the edge line with all edges that are not connected to this edge.
+        let mut xs: Vec<_> = (0..self.edges.len())
+            .filter(|i| *i != e && *i != edge.x1 && *i != edge.x2)
+            .filter_map(|i| line.segment_intersection(&self.edge_segment(i)))
+            .collect();
 
-        if inside && !cut_region.contains(&r) {
+        // No other edge may cross this edge.
+        if xs.iter().any(|x| x.pos >= a.pos && x.pos <= b.pos) {
             return None
-        } else if !inside && cut_region.contains(&r) {
+        }
+
+        // A neighbour on a convex corner bounds the region along the edge line, while the region
+        // continues along the line past a reflex corner.
+        if a.dir {
+            xs.push(LinePoint::new(a.pos, true));
+        }
+
+        if !b.dir {
+            xs.push(LinePoint::new(b.pos, false));
+        }
+
+        let cut_region = Region1D::new(xs)?;
+
+        if !cut_region.inside((a.pos + b.pos) / 2.0) {
             return None
         }
 
@@ -86,48 +223,31 @@ impl Region2D {
         self.edge_segment(e).intersects_line(line)
     }
 
-    fn inside_edge(&self, e: usize) -> Option<bool> {
+    // The intersections of the edge line with the lines of the edge neighbours, ordered along the
+    // edge line.
+    fn edge_ends(&self, e: usize) -> Option<(LinePoint, LinePoint)> {
         let edge = self.edges[e];
         let line = self.lines[edge.line];
         let x1 = self.lines[self.edges[edge.x1].line];
         let x2 = self.lines[self.edges[edge.x2].line];
 
-        let mut a = line.intersection(&x1)?;
-        let mut b = line.intersection(&x2)?;
+        let a = line.intersection(&x1)?;
+        let b = line.intersection(&x2)?;
 
-        // TODO: this is stupid
-        // Flip if inverted. We do this to compute a sensible region if this is an "inside" edge.
-        
-        Some(if a.pos < b.pos && !a.dir && b.dir {
-            true
-        } else if b.pos < a.pos && a.dir && !b.dir {
-            true
+        if a.pos <= b.pos {
+            Some((a, b))
         } else {
-            false
-        })
+            Some((b, a))
+        }
     }
 
     fn edge_region_safe(&self, e: usize) -> Option<Region1D> {
-        let edge = self.edges[e];
-        let line = self.lines[edge.line];
-        let x1 = self.lines[self.edges[edge.x1].line];
-        let x2 = self.lines[self.edges[edge.x2].line];
-
-        let mut a = line.intersection(&x1)?;
-        let mut b = line.intersection(&x2)?;
-
-        // TODO: this is stupid
-        // Flip if inverted. We do this to compute a sensible region if this is an "inside" edge.
-        
-        if a.pos < b.pos && !a.dir && b.dir {
-            a.dir = true;
-            b.dir = false;
-        } else if b.pos < a.pos && a.dir && !b.dir {
-            a.dir = false;
-            b.dir = true;
-        }
+        let (a, b) = self.edge_ends(e)?;
 
-        Region1D::new(vec![a, b])
+        Region1D::new(vec![
+            LinePoint::new(a.pos, true),
+            LinePoint::new(b.pos, false),
+        ])
     }
 
     pub fn edge_region(&self, e: usize) -> Region1D {
@@ -143,6 +263,98 @@ impl Region2D {
         &self.lines
     }
 
+    /// The line of an edge.
+    pub fn edge_line(&self, e: usize) -> Line {
+        self.lines[self.edges[e].line]
+    }
+
+    /// The edge before this edge when walking along the edge line.
+    pub fn edge_prev(&self, e: usize) -> usize {
+        let edge = self.edges[e];
+        if self.edge_x1_first(e) {
+            edge.x1
+        } else {
+            edge.x2
+        }
+    }
+
+    /// The edge after this edge when walking along the edge line.
+    pub fn edge_next(&self, e: usize) -> usize {
+        let edge = self.edges[e];
+        if self.edge_x1_first(e) {
+            edge.x2
+        } else {
+            edge.x1
+        }
+    }
+
+    fn edge_x1_first(&self, e: usize) -> bool {
+        let edge = self.edges[e];
+        let line = self.lines[edge.line];
+        let a = line.intersection(&self.lines[self.edges[edge.x1].line]);
+        let b = line.intersection(&self.lines[self.edges[edge.x2].line]);
+
+        match (a, b) {
+            (Some(a), Some(b)) => a.pos <= b.pos,
+            _ => true,
+        }
+    }
+
+    /// The start and end point of an edge, in the direction of the edge line.
+    ///
+    /// The inside of the region is always on the right hand side when walking from the start to
+    /// the end of an edge.
+    pub fn edge_points(&self, e: usize) -> (na::Point2<f64>, na::Point2<f64>) {
+        let segment = self.edge_segment(e);
+        (segment.p1(), segment.p2())
+    }
+
+    /// The vertex where edge `e` ends and the next edge starts.
+    pub fn edge_end_vertex(&self, e: usize) -> na::Point2<f64> {
+        self.edge_points(e).1
+    }
+
+    /// The closed loops of edges in the region.
+    ///
+    /// Each loop lists its edges in order such that each edge is followed by [`Self::edge_next`].
+    pub fn loops(&self) -> Vec<Vec<usize>> {
+        let mut visited = vec![false; self.edges.len()];
+        let mut loops = Vec::new();
+
+        for start in 0..self.edges.len() {
+            if visited[start] {
+                continue
+            }
+
+            let mut edge_loop = Vec::new();
+            let mut e = start;
+            while !visited[e] {
+                visited[e] = true;
+                edge_loop.push(e);
+                e = self.edge_next(e);
+            }
+
+            loops.push(edge_loop);
+        }
+
+        loops
+    }
+
+    /// The vertices of each loop in the region, see [`Self::loops`].
+    ///
+    /// Vertex `i` of a loop is the end point of edge `i` of the loop.
+    pub fn loop_vertices(&self) -> Vec<Vec<na::Point2<f64>>> {
+        self.loops().into_iter()
+            .map(|l| l.into_iter().map(|e| self.edge_end_vertex(e)).collect())
+            .collect()
+    }
+
+    pub fn area(&self) -> f64 {
+        self.loop_vertices().iter()
+            .flat_map(|vs| (0..vs.len()).map(move |i| vs[i].coords.perp(&vs[(i + 1) % vs.len()].coords)))
+            .sum::<f64>() / -2.0
+    }
+
     fn cut_region_filter<F: FnMut(usize) -> bool>(&self, line: Line, mut f: F) -> Option<Region1D> {
         let xs = (0..self.edges().len())
             .filter(|e| f(*e))
@@ -161,6 +373,56 @@ impl Region2D {
         self.cut_region_filter(line, |_| true).unwrap()
     }
 
+    /// Splits the region by a line.
+    ///
+    /// Returns the part of the region on the inside of the line and the part on the outside.
+    /// The cut edges of both parts lie on the line, with opposite directions. Either part may be
+    /// empty.
+    pub fn split(&self, line: Line) -> Option<(Region2D, Region2D)> {
+        Some((self.clip(line)?, self.clip(line.flip())?))
+    }
+
+    /// The part of the region on the inside of a line.
+    pub fn clip(&self, line: Line) -> Option<Region2D> {
+        Some(self.clip_sourced(line)?.0)
+    }
+
+    // The part of the region on the inside of a line, see `clip`, together with the edge of this
+    // region each edge of the result lies on, or `None` for the edges on the line.
+    pub(crate) fn clip_sourced(&self, line: Line) -> Option<(Region2D, Vec<Option<usize>>)> {
+        if self.is_empty() {
+            return Some((Self::empty(), Vec::new()))
+        }
+
+        let (mut lines, mut loops) = self.line_loops();
+
+        // Close the half-plane by a box around the region, where one side of the box is the line.
+        let vertices: Vec<_> = self.loop_vertices().into_iter().flatten().collect();
+        let o = line.point(line.project_point(vertices[0]).pos);
+        let size = 1.0 + vertices.iter().map(|v| (v - o).norm()).fold(0.0, f64::max);
+        let n = line.normal();
+        let dir = line.dir();
+
+        loops.push((lines.len()..lines.len() + 4).collect());
+        lines.extend([
+            line,
+            Line::from_point_normal(o + dir.into_inner() * size, -dir),
+            Line::from_point_normal(o + n.into_inner() * size, -n),
+            Line::from_point_normal(o - dir.into_inner() * size, dir),
+        ]);
+
+        let n = self.edges.len();
+        let (region, sources) = winding::resolve_sourced(&lines, &loops, |w| w >= 2)?;
+        Some((region, sources.into_iter().map(|l| (l < n).then_some(l)).collect()))
+    }
+
+    // The line of each edge and the loops of the region as lists of these lines, such that the
+    // line indices are the edge indices.
+    pub(crate) fn line_loops(&self) -> (Vec<Line>, Vec<Vec<usize>>) {
+        let lines = (0..self.edges.len()).map(|e| self.edge_line(e)).collect();
+        (lines, self.loops())
+    }
+
     pub fn inside(&self, point: na::Point2<f64>) -> bool {
         let line = Line::from_point_dir(point, na::Vector2::x());
 
@@ -171,6 +433,240 @@ impl Region2D {
 
         region.inside(point_pos)
     }
+
+    /// Inserts a new edge on a line after edge `after`, cutting the corner between `after` and
+    /// the next edge.
+    ///
+    /// The edge reuses a coincident line of the region if there is one. Returns the new edge, or
+    /// `None` if the result is not a valid region in which case the region is left unchanged.
+    /// Only the edges next to the new edge are re-validated.
+    pub fn insert_edge(&mut self, after: usize, line: Line) -> Option<usize> {
+        self.edit(|r, log| {
+            let next = r.edge_next(after);
+            let line = r.add_line(log, line);
+            let e = r.push_edge(log, Edge::new(line, after, next));
+            r.replace_neighbour(log, after, next, e);
+            r.replace_neighbour(log, next, after, e);
+
+            Some((e, vec![after, e, next]))
+        })
+    }
+
+    /// Removes an edge, connecting the edges before and after it.
+    ///
+    /// The edges are kept in a list that is compacted by moving the last edge into the place of
+    /// the removed edge, so the last edge is renumbered to `e`. If no other edge is on the line
+    /// of the removed edge the line is removed as well, and the last line is renumbered in the
+    /// same way. Returns `None` if the result is not a valid region in which case the region is
+    /// left unchanged.
+    pub fn remove_edge(&mut self, e: usize) -> Option<()> {
+        self.edit(|r, log| {
+            let prev = r.edge_prev(e);
+            let next = r.edge_next(e);
+
+            // A loop needs at least three edges.
+            if r.edge_next(next) == prev {
+                return None
+            }
+
+            r.replace_neighbour(log, prev, e, next);
+            r.replace_neighbour(log, next, e, prev);
+
+            let line = r.edges[e].line;
+            let mut affected = vec![prev, next];
+            let last = r.swap_remove_edge(log, e);
+            for a in &mut affected {
+                if *a == last {
+                    *a = e;
+                }
+            }
+
+            r.remove_unused_line(log, line);
+
+            Some(((), affected))
+        })
+    }
+
+    /// Replaces a line of the region.
+    ///
+    /// Returns `None` if the result is not a valid region in which case the region is left
+    /// unchanged. Only the edges on the line and their neighbours are re-validated.
+    pub fn move_line(&mut self, line: usize, new: Line) -> Option<()> {
+        self.edit(|r, log| {
+            log.push(Change::SetLine(line, r.lines[line]));
+            r.lines[line] = new;
+
+            let affected = (0..r.edges.len())
+                .filter(|e| r.edges[*e].line == line)
+                .flat_map(|e| [e, r.edges[e].x1, r.edges[e].x2])
+                .collect();
+
+            Some(((), affected))
+        })
+    }
+
+    /// Applies a split of an edge, replacing the edge by a chain of edges on new lines.
+    ///
+    /// The chain goes from the start to the end of the original edge through the lines of
+    /// `new_inside` followed by the lines of `new_outside`, and the result lists the new edges in
+    /// the same grouping. The first new edge takes the index of the original edge. Coincident
+    /// lines of the region are reused, and the line of the original edge is removed if no edge is
+    /// left on it, like in [`Self::remove_edge`]. Returns `None` if the result is not a valid
+    /// region in which case the region is left unchanged.
+    pub fn apply_split(&mut self, split: &Split<usize, Line>) -> Option<SplitResult<usize>> {
+        self.edit(|r, log| {
+            let e = split.orig;
+            let prev = r.edge_prev(e);
+            let next = r.edge_next(e);
+            let old_line = r.edges[e].line;
+            let lines: Vec<_> = split.new_inside.iter().chain(&split.new_outside).copied().collect();
+
+            if lines.is_empty() {
+                return None
+            }
+
+            // The new edges in chain order, where the first reuses the original edge.
+            let mut chain = vec![e];
+            for _ in 1..lines.len() {
+                chain.push(r.push_edge(log, r.edges[e]));
+            }
+
+            for (i, line) in lines.into_iter().enumerate() {
+                let line = r.add_line(log, line);
+                let x1 = if i == 0 { prev } else { chain[i - 1] };
+                let x2 = chain.get(i + 1).copied().unwrap_or(next);
+                r.set_edge(log, chain[i], Edge::new(line, x1, x2));
+            }
+
+            r.replace_neighbour(log, next, e, chain[chain.len() - 1]);
+            r.remove_unused_line(log, old_line);
+
+            let res = SplitResult {
+                new_inside: chain[..split.new_inside.len()].to_vec(),
+                new_outside: chain[split.new_inside.len()..].to_vec(),
+            };
+
+            let mut affected = chain;
+            affected.extend([prev, next]);
+
+            Some((res, affected))
+        })
+    }
+
+    // Applies an edit and re-validates the edges it affected, undoing the changes recorded by the
+    // edit if it fails or leaves the region invalid.
+    fn edit<T, F>(&mut self, f: F) -> Option<T>
+    where
+        F: FnOnce(&mut Self, &mut Vec<Change>) -> Option<(T, Vec<usize>)>,
+    {
+        let mut log = Vec::new();
+
+        let res = f(self, &mut log).and_then(|(res, affected)| {
+            for e in &affected {
+                self.edge_region_safe(*e)?;
+            }
+
+            for e in &affected {
+                self.check_edge(*e)?;
+            }
+
+            Some(res)
+        });
+
+        if res.is_none() {
+            for change in log.into_iter().rev() {
+                match change {
+                    Change::SetLine(i, line) => self.lines[i] = line,
+                    Change::SetEdge(i, edge) => self.edges[i] = edge,
+                    Change::PushLine => { self.lines.pop(); }
+                    Change::PushEdge => { self.edges.pop(); }
+                    Change::PopLine(line) => self.lines.push(line),
+                    Change::PopEdge(edge) => self.edges.push(edge),
+                }
+            }
+        }
+
+        res
+    }
+
+    // Finds a coincident line or adds a new line.
+    fn add_line(&mut self, log: &mut Vec<Change>, line: Line) -> usize {
+        self.lines.iter().position(|l| l.coincident(&line)).unwrap_or_else(|| {
+            log.push(Change::PushLine);
+            self.lines.push(line);
+            self.lines.len() - 1
+        })
+    }
+
+    // Removes a line if no edge is on it, moving the last line into its place.
+    fn remove_unused_line(&mut self, log: &mut Vec<Change>, line: usize) {
+        if self.edges.iter().any(|e| e.line == line) {
+            return
+        }
+
+        let last = self.lines.len() - 1;
+        log.push(Change::SetLine(line, self.lines[line]));
+        log.push(Change::PopLine(self.lines[last]));
+        self.lines.swap_remove(line);
+
+        for e in 0..self.edges.len() {
+            if self.edges[e].line == last {
+                self.set_edge(log, e, Edge { line, ..self.edges[e] });
+            }
+        }
+    }
+
+    fn set_edge(&mut self, log: &mut Vec<Change>, e: usize, edge: Edge) {
+        log.push(Change::SetEdge(e, self.edges[e]));
+        self.edges[e] = edge;
+    }
+
+    fn push_edge(&mut self, log: &mut Vec<Change>, edge: Edge) -> usize {
+        log.push(Change::PushEdge);
+        self.edges.push(edge);
+        self.edges.len() - 1
+    }
+
+    // Removes an edge by moving the last edge into its place, and returns the old index of the
+    // moved edge.
+    fn swap_remove_edge(&mut self, log: &mut Vec<Change>, e: usize) -> usize {
+        let last = self.edges.len() - 1;
+        log.push(Change::SetEdge(e, self.edges[e]));
+        log.push(Change::PopEdge(self.edges[last]));
+        self.edges.swap_remove(e);
+
+        if e != last {
+            let edge = self.edges[e];
+            self.replace_neighbour(log, edge.x1, last, e);
+            self.replace_neighbour(log, edge.x2, last, e);
+        }
+
+        last
+    }
+
+    fn replace_neighbour(&mut self, log: &mut Vec<Change>, e: usize, old: usize, new: usize) {
+        let mut edge = self.edges[e];
+        if edge.x1 == old {
+            edge.x1 = new;
+        } else if edge.x2 == old {
+            edge.x2 = new;
+        } else {
+            return
+        }
+
+        self.set_edge(log, e, edge);
+    }
+}
+
+// A change made by an edit of a region, with the values needed to undo it.
+#[derive(Copy, Clone, Debug)]
+enum Change {
+    SetLine(usize, Line),
+    SetEdge(usize, Edge),
+    PushLine,
+    PushEdge,
+    PopLine(Line),
+    PopEdge(Edge),
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -190,9 +686,170 @@ impl Edge {
     }
 }
 
+/// A piece of the boundary of a cell, the part of a line given by a 1d region.
+#[derive(Clone, Debug)]
+pub struct EdgeRegion {
+    pub line: Line,
+    pub region: Region1D,
+}
+
+impl EdgeRegion {
+    /// The segments of the edge, ordered along the line.
+    pub fn segments(&self) -> impl '_ + Iterator<Item = LineSegment> {
+        let points = self.region.points();
+        (0..points.len()).step_by(2)
+            .map(move |i| LineSegment::new(self.line.point(points[i].pos), self.line.point(points[i + 1].pos)))
+    }
+
+    pub fn length(&self) -> f64 {
+        let points = self.region.points();
+        (0..points.len()).step_by(2).map(|i| points[i + 1].pos - points[i].pos).sum()
+    }
+}
+
+/// A cell of a [`Region2D`], a single boundary loop filled on the inside.
+///
+/// The boundary is stored as a list of edges that may be split into any number of pieces.
+#[derive(Clone, Debug)]
+pub struct Cell2D {
+    edges: Vec<EdgeRegion>,
+}
+
+impl Cell2D {
+    pub fn new(edges: Vec<EdgeRegion>) -> Self {
+        Self {
+            edges,
+        }
+    }
+
+    pub fn edges(&self) -> &[EdgeRegion] {
+        &self.edges
+    }
+
+    /// The part of a line inside the cell, see [`Region2D::cut_region`].
+    pub fn cut_region(&self, line: Line) -> Region1D {
+        let xs = self.edges.iter()
+            .flat_map(|e| e.segments())
+            .filter_map(|s| line.segment_intersection(&s))
+            .collect();
+
+        // The line may only touch the cell.
+        Region1D::new(xs).unwrap_or_else(Region1D::empty)
+    }
+
+    // Classifies the edges of this cell against another cell.
+    fn classify(&self, other: &Cell2D, splits: &mut Vec<SubRegionSplit<Self>>, inside: &mut Vec<usize>) {
+        for (i, e) in self.edges.iter().enumerate() {
+            let (a, b) = e.region.split(&other.cut_region(e.line));
+
+            if b.is_empty() && !a.is_empty() {
+                inside.push(i);
+            } else if !a.is_empty() {
+                splits.push(Split {
+                    orig: i,
+                    new_outside: vec![EdgeRegion { line: e.line, region: b }],
+                    new_inside: vec![EdgeRegion { line: e.line, region: a }],
+                });
+            }
+        }
+    }
+}
+
+impl Cell for Cell2D {
+    type SubRegion = EdgeRegion;
+    type SubRegionRef = usize;
+    type SubRegionIter = std::ops::Range<usize>;
+
+    fn subregions(&self) -> Self::SubRegionIter {
+        0..self.edges.len()
+    }
+
+    fn subregion(&self, rref: usize) -> EdgeRegion {
+        self.edges[rref].clone()
+    }
+
+    fn intersection(&self, other: &Cell2D) -> Option<CellIntersection<Self>> {
+        let mut x = Intersection::new();
+        self.classify(other, &mut x.splits_c1, &mut x.inside_c1);
+        other.classify(self, &mut x.splits_c2, &mut x.inside_c2);
+
+        if x.is_empty() {
+            None
+        } else {
+            Some(x)
+        }
+    }
+}
+
+impl CellEdit for Cell2D {
+    fn apply_split(&mut self, split: &SubRegionSplit<Self>) -> SplitResult<usize> {
+        // The first piece takes the place of the original edge, the rest are added at the end.
+        let mut res = SplitResult::new();
+        let mut first = true;
+
+        for (e, inside) in split.new_inside.iter().map(|e| (e, true))
+            .chain(split.new_outside.iter().map(|e| (e, false)))
+        {
+            let i = if first {
+                first = false;
+                self.edges[split.orig] = e.clone();
+                split.orig
+            } else {
+                self.edges.push(e.clone());
+                self.edges.len() - 1
+            };
+
+            if inside {
+                res.new_inside.push(i);
+            } else {
+                res.new_outside.push(i);
+            }
+        }
+
+        res
+    }
+}
+
+/// The cells of a region are its loops, where holes are the loops going counterclockwise.
+impl Region for Region2D {
+    type Cell = Cell2D;
+    type CellRef = usize;
+    type CellIter = std::ops::Range<usize>;
+
+    fn cells(&self) -> Self::CellIter {
+        0..self.loops().len()
+    }
+
+    fn cell(&self, cref: usize) -> Cell2D {
+        let hole = self.cell_hole(cref);
+        let edges = self.loops()[cref].iter()
+            .map(|e| {
+                let line = self.edge_line(*e);
+                let region = self.edge_region(*e);
+                if hole {
+                    EdgeRegion { line: line.flip(), region: region.flip() }
+                } else {
+                    EdgeRegion { line, region }
+                }
+            })
+            .collect();
+
+        Cell2D::new(edges)
+    }
+
+    fn cell_hole(&self, cref: usize) -> bool {
+        let vs = &self.loop_vertices()[cref];
+        let area: f64 = (0..vs.len())
+            .map(|i| vs[i].coords.perp(&vs[(i + 1) % vs.len()].coords))
+            .sum();
+        area > 0.0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::{l_shape, line, polygon, rect_points, square};
 
     fn test_lines() -> Vec<Line> {
         let p1 = na::Point2::new(0.0, 0.0);
@@ -288,6 +945,226 @@ mod tests {
         assert!(!region.inside(na::Point2::new(3.5, 0.5)));
     }
 
+    #[test]
+    fn concave_region_valid() {
+        let points = [
+            na::Point2::new(0.0, 0.0),
+            na::Point2::new(0.0, 3.0),
+            na::Point2::new(1.0, 3.0),
+            na::Point2::new(1.0, 1.0),
+            na::Point2::new(3.0, 1.0),
+            na::Point2::new(3.0, 0.0),
+        ];
+
+        let lines = (0..6)
+            .map(|i| Line::from_two_points(points[i], points[(i + 1) % 6]))
+            .collect::<Vec<_>>();
+        let edges = (0..6)
+            .map(|i| Edge::new(i, (i + 5) % 6, (i + 1) % 6))
+            .collect::<Vec<_>>();
+
+        let region = Region2D::new(lines, edges).unwrap();
+
+        assert!(region.inside(na::Point2::new(0.5, 2.0)));
+        assert!(region.inside(na::Point2::new(2.0, 0.5)));
+        assert!(!region.inside(na::Point2::new(2.0, 2.0)));
+        assert_eq!(region.loops().len(), 1);
+
+        // A self intersecting bow tie
+        let points = [points[0], points[1], points[5], na::Point2::new(3.0, 3.0)];
+        let lines = (0..4)
+            .map(|i| Line::from_two_points(points[i], points[(i + 1) % 4]))
+            .collect::<Vec<_>>();
+        let edges = (0..4)
+            .map(|i| Edge::new(i, (i + 3) % 4, (i + 1) % 4))
+            .collect::<Vec<_>>();
+
+        assert!(Region2D::new(lines, edges).is_none());
+    }
+
+    #[test]
+    fn simplify_region() {
+        let edges = vec![
+            Edge::new(0, 1, 3),
+            Edge::new(1, 0, 2),
+            Edge::new(2, 1, 3),
+            Edge::new(3, 0, 2),
+        ];
+
+        let region = Region2D::new(test_lines(), edges).unwrap().simplify().unwrap();
+        assert_eq!(region.lines().len(), 4);
+        assert_eq!(region.edges().len(), 4);
+        assert!((region.area() - 9.0).abs() < 1e-9);
+
+        // Two squares with their bottom edges on the same line, and a third square with its top
+        // edge on that line but in the opposite direction.
+        let square = |x: f64, y: f64| vec![
+            na::Point2::new(x, y),
+            na::Point2::new(x, y + 1.0),
+            na::Point2::new(x + 0.5, y + 1.0),
+            na::Point2::new(x + 1.0, y + 1.0),
+            na::Point2::new(x + 1.0, y),
+        ];
+
+        let region = Region2D::from_polygons(&[square(0.0, 0.0), square(2.0, 0.0), square(4.0, -1.0)])
+            .unwrap();
+        assert_eq!(region.lines().len(), 15);
+        assert_eq!(region.edges().len(), 12);
+
+        let simple = region.simplify().unwrap();
+        assert_eq!(simple.lines().len(), 10);
+        assert_eq!(simple.edges().len(), 12);
+        assert!((simple.area() - 3.0).abs() < 1e-9);
+        assert!(simple.inside(na::Point2::new(4.5, -0.5)));
+        assert!(!simple.inside(na::Point2::new(4.5, 0.5)));
+    }
+
+    #[test]
+    fn transform_region() {
+        let region = l_shape();
+
+        let iso = na::Isometry2::new(na::Vector2::new(-2.0, 5.0), 2.0);
+        let moved = region.transform(iso).unwrap();
+        assert!((moved.area() - 5.0).abs() < 1e-9);
+        assert!(moved.inside(iso * na::Point2::new(0.5, 2.0)));
+        assert!(!moved.inside(iso * na::Point2::new(2.0, 2.0)));
+
+        let scaled = region.transform(na::Similarity2::new(na::Vector2::zeros(), 0.0, 0.5)).unwrap();
+        assert!((scaled.area() - 1.25).abs() < 1e-9);
+
+        let mirror = na::Affine2::from_matrix_unchecked(na::Matrix3::new(
+            1.0, 0.0, 0.0,
+            0.0, -2.0, 0.0,
+            0.0, 0.0, 1.0,
+        ));
+        let mirrored = region.transform(mirror).unwrap();
+        assert!((mirrored.area() - 10.0).abs() < 1e-9);
+        assert!(mirrored.inside(na::Point2::new(0.5, -4.0)));
+        assert!(!mirrored.inside(na::Point2::new(2.0, -4.0)));
+
+        let flat = na::Affine2::from_matrix_unchecked(na::Matrix3::new(
+            1.0, 0.0, 0.0,
+            0.0, 0.0, 0.0,
+            0.0, 0.0, 1.0,
+        ));
+        assert_eq!(region.transform(flat).unwrap_err(), TransformError::Degenerate);
+        assert_eq!(region.transform(na::Matrix3::new(
+            1.0, 0.0, 0.0,
+            0.0, 1.0, 0.0,
+            0.0, 0.1, 1.0,
+        )).unwrap_err(), TransformError::NotAffine);
+
+        // Far from the origin the vertices are rounded onto each other.
+        let far = na::Translation2::new(1e17, 0.0);
+        assert_eq!(region.transform(far).unwrap_err(), TransformError::Invalid);
+    }
+
+    #[test]
+    fn split_region() {
+        let square = square();
+
+        let line = Line::from_two_points(na::Point2::new(0.0, 0.0), na::Point2::new(1.0, 1.0));
+        let (a, b) = square.split(line).unwrap();
+        assert!((a.area() - 4.5).abs() < 1e-9);
+        assert!((b.area() - 4.5).abs() < 1e-9);
+        assert!(a.inside(na::Point2::new(2.0, 1.0)));
+        assert!(b.inside(na::Point2::new(1.0, 2.0)));
+        assert_eq!(a.edges().len(), 3);
+        assert_eq!(b.edges().len(), 3);
+
+        // Splitting along an edge keeps the whole region on one side.
+        let line = Line::from_two_points(na::Point2::new(0.0, 3.0), na::Point2::new(1.0, 3.0));
+        let (a, b) = square.split(line).unwrap();
+        assert!((a.area() - 9.0).abs() < 1e-9);
+        assert!(b.is_empty());
+
+        // A U shape split across its prongs, the inside of the line is below it.
+        let u = Region2D::from_polygons(&[vec![
+            na::Point2::new(0.0, 0.0),
+            na::Point2::new(0.0, 3.0),
+            na::Point2::new(1.0, 3.0),
+            na::Point2::new(1.0, 1.0),
+            na::Point2::new(2.0, 1.0),
+            na::Point2::new(2.0, 3.0),
+            na::Point2::new(3.0, 3.0),
+            na::Point2::new(3.0, 0.0),
+        ]]).unwrap();
+
+        let line = Line::from_two_points(na::Point2::new(0.0, 2.0), na::Point2::new(1.0, 2.0));
+        let (bottom, top) = u.split(line).unwrap();
+        assert_eq!(top.loops().len(), 2);
+        assert_eq!(bottom.loops().len(), 1);
+        assert!((top.area() - 2.0).abs() < 1e-9);
+        assert!((bottom.area() - 5.0).abs() < 1e-9);
+        assert!(bottom.lines().iter().any(|l| l.coincident(&line)));
+        assert!(top.lines().iter().any(|l| l.coincident(&line.flip())));
+    }
+
+    #[test]
+    fn edit_region() {
+        // Cut the upper right corner.
+        let mut region = square();
+        let e = region.insert_edge(1, line(2.0, 3.0, 3.0, 2.0)).unwrap();
+        assert_eq!(region.edges().len(), 5);
+        assert_eq!(region.edge_next(1), e);
+        assert!((region.area() - 8.5).abs() < 1e-9);
+        assert!(Region2D::new(region.lines().to_vec(), region.edges().to_vec()).is_some());
+
+        // A line that does not cut the corner is rolled back.
+        let before = region.edges().to_vec();
+        assert!(region.insert_edge(0, line(2.0, 3.0, 3.0, 2.0)).is_none());
+        assert_eq!(region.edges().len(), before.len());
+        assert!((region.area() - 8.5).abs() < 1e-9);
+
+        region.remove_edge(e).unwrap();
+        assert_eq!(region.edges().len(), 4);
+        assert!((region.area() - 9.0).abs() < 1e-9);
+        assert!(region.remove_edge(0).is_none());
+        assert_eq!(region.edges().len(), 4);
+
+        // The line of a removed edge is dropped with it.
+        assert_eq!(region.lines().len(), 4);
+        let lines = region.lines().to_vec();
+        assert!(region.remove_edge(2).is_none());
+        assert!(region.lines().iter().zip(&lines).all(|(a, b)| a.coincident(b)));
+
+        // A coincident line of another loop is reused, and kept when the edge is removed.
+        let mut region = Region2D::from_polygons(&[
+            rect_points(0.0, 0.0, 3.0, 3.0),
+            polygon(&[(5.0, 0.0), (6.0, -1.0), (4.0, -1.0)]),
+        ]).unwrap();
+        assert_eq!(region.lines().len(), 7);
+        let e = region.insert_edge(1, line(2.0, 3.0, 3.0, 2.0)).unwrap();
+        assert_eq!(region.lines().len(), 7);
+        assert!((region.area() - 9.5).abs() < 1e-9);
+        region.remove_edge(e).unwrap();
+        assert_eq!(region.lines().len(), 7);
+        assert!((region.area() - 10.0).abs() < 1e-9);
+
+        // Move the left side of the square.
+        let mut region = square();
+        let left = region.edges()[0].line;
+        region.move_line(left, line(-1.0, 0.0, -1.0, 3.0)).unwrap();
+        assert!((region.area() - 12.0).abs() < 1e-9);
+        assert!(region.move_line(left, line(0.0, 3.0, 0.0, 0.0)).is_none());
+        assert!((region.area() - 12.0).abs() < 1e-9);
+
+        // Replace the top side by a notch going down into the region.
+        let mut region = square();
+        let res = region.apply_split(&Split {
+            orig: 1,
+            new_inside: vec![line(0.0, 3.0, 1.0, 3.0), line(1.0, 3.0, 1.0, 2.0)],
+            new_outside: vec![line(1.0, 2.0, 2.0, 2.0), line(2.0, 2.0, 2.0, 3.0), line(2.0, 3.0, 3.0, 3.0)],
+        }).unwrap();
+        assert_eq!(res.new_inside, vec![1, 4]);
+        assert_eq!(res.new_outside, vec![5, 6, 7]);
+        assert_eq!(region.lines().len(), 7);
+        assert_eq!(region.loops().len(), 1);
+        assert!((region.area() - 8.0).abs() < 1e-9);
+        assert!(!region.inside(na::Point2::new(1.5, 2.5)));
+        assert!(Region2D::new(region.lines().to_vec(), region.edges().to_vec()).is_some());
+    }
+
     #[test]
     fn cut_regions_corner_stable() {
         let mut val: f64 = 10.316314;
@@ -315,7 +1192,7 @@ mod tests {
             let ray_line_2 = Line::from_point_dir(p2, transform * na::Vector2::y());
 
             let r1 = region.cut_region(ray_line_1);
-            assert!(r1.points().len() == 0 || r1.points().len() == 2);
+            assert!(r1.points().is_empty() || r1.points().len() == 2);
 
             let r2 = region.cut_region(ray_line_2);
             assert_eq!(r2.points().len(), 2);
@@ -0,0 +1,906 @@
+use std::collections::HashMap;
+
+use crate::brep_1d::Region1D;
+use crate::brep_2d::Region2D;
+use crate::geometry::{Line, LinePoint, EPSILON};
+use crate::geometry_3d::{Line3D, Plane};
+use crate::mesh::Mesh;
+use crate::relation::BooleanOp;
+use crate::winding;
+
+// Generic directions used to perturb lines that hit edges or vertices exactly, see
+// `Region3D::face_intersection`. The distance is relative to the size of the coordinates of the
+// face, so that it stays above the rounding errors and below the size of the face.
+const PERTURBATION_DISTANCE: f64 = 1e-7;
+const PERTURBATION: [[f64; 3]; 2] = [[0.5395, 0.2981, 0.7875], [-0.3261, 0.8117, 0.4847]];
+
+/// A 3d boundary region.
+///
+/// Consists of a set of planes and faces, where each face is a [`Region2D`] in the local frame of
+/// its plane. The lines of a face are the intersections of its plane with the planes of the
+/// neighbouring faces, and each edge of a face is linked to the edge of the neighbouring face on
+/// the other side.
+///
+/// ## Invariants:
+/// - Each face is a proper 2d region
+/// - Each edge is linked to exactly one edge of another face, which is linked back (checked by
+///   structure)
+/// - The line of an edge is the intersection of the planes of the two faces, and linked edges go
+///   between the same vertices in opposite directions
+/// - No faces intersect
+/// - The inside of the region is on the inside of each face plane (the side the normal points to)
+#[derive(Clone, Debug)]
+pub struct Region3D {
+    planes: Vec<Plane>,
+    faces: Vec<Face3D>,
+}
+
+/// A face of a [`Region3D`].
+#[derive(Clone, Debug)]
+pub struct Face3D {
+    pub plane: usize,
+    pub region: Region2D,
+    /// The edge linked to each edge of the face region.
+    pub links: Vec<FaceEdge>,
+}
+
+/// A reference to an edge of a face.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FaceEdge {
+    pub face: usize,
+    pub edge: usize,
+}
+
+impl Face3D {
+    pub fn new(plane: usize, region: Region2D, links: Vec<FaceEdge>) -> Self {
+        Self {
+            plane,
+            region,
+            links,
+        }
+    }
+}
+
+impl Region3D {
+    pub fn new(planes: Vec<Plane>, faces: Vec<Face3D>) -> Option<Self> {
+        let region = Self {
+            planes,
+            faces,
+        };
+
+        region.check()?;
+
+        Some(region)
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            planes: Vec::new(),
+            faces: Vec::new(),
+        }
+    }
+
+    /// Creates a region from faces given by a plane and a region in the local frame of the plane.
+    ///
+    /// The neighbouring plane of each line is searched among all planes as the plane whose
+    /// intersection with the plane of the face is the line, taking the first if there are several.
+    /// Use [`Region3D::from_faces_with_neighbours`] if the neighbouring planes are known.
+    pub fn from_faces(planes: Vec<Plane>, faces: Vec<(usize, Region2D)>) -> Option<Self> {
+        let faces = faces.into_iter()
+            .map(|(p, region)| {
+                let plane = planes.get(p)?;
+                let line_planes = region.lines().iter()
+                    .map(|l| {
+                        (0..planes.len()).find(|q| {
+                            plane.intersection_line(&planes[*q]).is_some_and(|m| m.coincident(l) || m.coincident(&l.flip()))
+                        })
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some((p, region, line_planes))
+            })
+            .collect::<Option<_>>()?;
+
+        Self::from_faces_with_neighbours(planes, faces)
+    }
+
+    /// Creates a region from faces given by a plane, a region in the local frame of the plane and
+    /// for each line of the region the plane of the neighbouring face, whose intersection with
+    /// the plane of the face is the line.
+    ///
+    /// The edges of the faces are linked to the edges of other faces on the same two planes that
+    /// go between the same vertices in the opposite direction. Vertices are identified by the
+    /// three planes they are the intersection of, and only vertices where more than three planes
+    /// meet are compared by position.
+    pub fn from_faces_with_neighbours(planes: Vec<Plane>, faces: Vec<(usize, Region2D, Vec<usize>)>) -> Option<Self> {
+        // The neighbouring plane of each edge of each face.
+        let mut edge_planes = Vec::new();
+        for (plane, region, line_planes) in &faces {
+            let valid = |q: &usize| *q < planes.len();
+            if !valid(plane) || line_planes.len() != region.lines().len() || !line_planes.iter().all(valid) {
+                return None
+            }
+            edge_planes.push(region.edges().iter().map(|e| line_planes[e.line]).collect::<Vec<_>>());
+        }
+
+        let mut pairs: HashMap<(usize, usize), Vec<FaceEdge>> = HashMap::new();
+        for (f, (plane, _, _)) in faces.iter().enumerate() {
+            for (e, q) in edge_planes[f].iter().enumerate() {
+                pairs.entry((*plane, *q)).or_default().push(FaceEdge { face: f, edge: e });
+            }
+        }
+
+        // The start and end vertex of an edge, as the sorted planes meeting there.
+        let vertices = |x: FaceEdge| {
+            let (plane, region, _) = &faces[x.face];
+            let vertex = |a: usize, b: usize| {
+                let mut v = [*plane, edge_planes[x.face][a], edge_planes[x.face][b]];
+                v.sort();
+                v
+            };
+            (vertex(region.edge_prev(x.edge), x.edge), vertex(x.edge, region.edge_next(x.edge)))
+        };
+        let same = |a: [usize; 3], b: [usize; 3]| {
+            let point = |v: [usize; 3]| Plane::intersection_point(v.map(|p| &planes[p]));
+            a == b || point(a).zip(point(b)).is_some_and(|(a, b)| (a - b).norm() <= EPSILON)
+        };
+
+        let mut links = Vec::new();
+        for (f, (plane, _, _)) in faces.iter().enumerate() {
+            let mut face_links = Vec::new();
+            for (e, q) in edge_planes[f].iter().enumerate() {
+                let (a, b) = vertices(FaceEdge { face: f, edge: e });
+                let link = pairs.get(&(*q, *plane))?.iter()
+                    .copied()
+                    .find(|x| {
+                        let (c, d) = vertices(*x);
+                        x.face != f && same(a, d) && same(b, c)
+                    })?;
+                face_links.push(link);
+            }
+            links.push(face_links);
+        }
+
+        let faces = faces.into_iter()
+            .zip(links)
+            .map(|((plane, region, _), links)| Face3D::new(plane, region, links))
+            .collect();
+
+        Self::new(planes, faces)
+    }
+
+    /// Creates a region from a polyhedron.
+    ///
+    /// Each face is a list of loops of vertex indices. Seen from the outside of the polyhedron
+    /// outer loops are counterclockwise and holes are clockwise. Each edge must be shared by
+    /// exactly two faces in opposite directions, and neighbouring faces may not be coplanar. The
+    /// lines of the faces are computed from the face planes, so the vertices only need to be
+    /// approximately on the planes.
+    pub fn from_polyhedron(vertices: &[na::Point3<f64>], faces: &[Vec<Vec<usize>>]) -> Option<Self> {
+        let mut planes = Vec::new();
+        let mut edge_faces = HashMap::new();
+
+        for (f, face) in faces.iter().enumerate() {
+            // Newell's method, the normal points out of the polyhedron.
+            let mut normal = na::Vector3::zeros();
+            let mut center = na::Vector3::zeros();
+            let mut count = 0.0;
+
+            for l in face {
+                for i in 0..l.len() {
+                    let (a, b) = (vertices[l[i]], vertices[l[(i + 1) % l.len()]]);
+                    normal += a.coords.cross(&b.coords);
+                    center += a.coords;
+                    count += 1.0;
+                    edge_faces.insert((l[i], l[(i + 1) % l.len()]), f);
+                }
+            }
+
+            let normal = na::Unit::try_new(-normal, EPSILON)?;
+            planes.push(Plane::from_point_normal(na::Point3::from(center / count), normal));
+        }
+
+        let mut regions = Vec::new();
+
+        for (f, face) in faces.iter().enumerate() {
+            let plane = planes[f];
+            let mut lines = Vec::new();
+            let mut line_planes = Vec::new();
+            let mut loops = Vec::new();
+
+            for l in face {
+                let mut face_loop = Vec::new();
+
+                for i in 0..l.len() {
+                    let (a, b) = (l[i], l[(i + 1) % l.len()]);
+                    let g = *edge_faces.get(&(b, a))?;
+                    let line = plane.intersection_line(&planes[g])?;
+                    let dir = plane.to_local(vertices[b]) - plane.to_local(vertices[a]);
+
+                    face_loop.push(lines.len());
+                    lines.push(if line.dir().dot(&dir) >= 0.0 { line } else { line.flip() });
+                    line_planes.push(g);
+                }
+
+                loops.push(face_loop);
+            }
+
+            regions.push((f, Region2D::from_loops(lines, &loops)?, line_planes));
+        }
+
+        Self::from_faces_with_neighbours(planes, regions)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.faces.is_empty()
+    }
+
+    pub fn planes(&self) -> &[Plane] {
+        &self.planes
+    }
+
+    pub fn faces(&self) -> &[Face3D] {
+        &self.faces
+    }
+
+    pub fn face_plane(&self, f: usize) -> Plane {
+        self.planes[self.faces[f].plane]
+    }
+
+    /// The start and end point of an edge of a face.
+    ///
+    /// The points are the intersections of the plane of the face with the planes of the linked
+    /// faces of the edge and its neighbours, so all faces meeting at a vertex agree on its exact
+    /// position.
+    pub fn edge_points(&self, edge: FaceEdge) -> (na::Point3<f64>, na::Point3<f64>) {
+        self.edge_points_safe(edge).unwrap()
+    }
+
+    fn edge_points_safe(&self, edge: FaceEdge) -> Option<(na::Point3<f64>, na::Point3<f64>)> {
+        let face = &self.faces[edge.face];
+        let region = &face.region;
+        let plane = |e: usize| self.planes.get(self.faces.get(face.links.get(e)?.face)?.plane);
+
+        let p = self.planes.get(face.plane)?;
+        let this = plane(edge.edge)?;
+        let prev = plane(region.edge_prev(edge.edge))?;
+        let next = plane(region.edge_next(edge.edge))?;
+
+        Some((
+            Plane::intersection_point([p, prev, this])?,
+            Plane::intersection_point([p, this, next])?,
+        ))
+    }
+
+    /// The intersection of a line with a face.
+    ///
+    /// This is stable such that a line through an edge or vertex shared by several faces
+    /// intersects the faces consistently, like [`Line::segment_intersection`] in 2d:
+    /// - If the line goes through the boundary into the region exactly one face is intersected.
+    /// - If the line only touches the boundary either none or an even number of the faces are
+    ///   intersected, with alternating directions.
+    ///
+    /// This is done by moving the line a small distance in a fixed generic direction, and
+    /// intersecting the face with the moved line. The position of the intersection is the
+    /// position along the original line.
+    pub fn face_intersection(&self, f: usize, line: &Line3D) -> Option<LinePoint> {
+        let plane = self.face_plane(f);
+        let x = plane.intersection(line)?;
+
+        // The direction the intersection point moves in the plane when the line is moved.
+        let d = line.dir().into_inner();
+        let w = PERTURBATION.iter()
+            .map(|a| d.cross(&na::Vector3::from(*a)))
+            .find(|w| w.norm() > 0.1)
+            .unwrap();
+        let delta = w - d * plane.normal().dot(&w) / plane.normal().dot(&d);
+
+        let frame = plane.frame();
+        let q = plane.to_local(line.point(x.pos));
+        let delta = frame.inverse_transform_vector(&delta).xy();
+
+        // The point is in the face if the face contains the point it moves to.
+        let region = &self.faces[f].region;
+        let size = (0..region.edges().len())
+            .map(|e| region.edge_end_vertex(e).coords.amax())
+            .fold(q.coords.amax(), f64::max);
+        let inside = region.inside(q + delta * PERTURBATION_DISTANCE * size);
+
+        inside.then_some(x)
+    }
+
+    /// The cut of the region along a line.
+    pub fn cut_region(&self, line: Line3D) -> Region1D {
+        let xs = (0..self.faces.len())
+            .filter_map(|f| self.face_intersection(f, &line))
+            .collect();
+
+        // All cut regions of a proper Region3D should be valid
+        Region1D::new(xs).unwrap()
+    }
+
+    /// Checks if a point is inside the region, by casting a line through it.
+    pub fn inside(&self, point: na::Point3<f64>) -> bool {
+        let line = Line3D::from_point_dir(point, na::Vector3::x());
+        self.cut_region(line).inside(0.0)
+    }
+
+    /// The cross-section of the region with a plane, as a region in the local frame of the plane.
+    ///
+    /// The edges of the section lie on the intersections of the plane with the face planes. To
+    /// be consistent when the plane goes through edges or vertices, vertices within `EPSILON` of
+    /// the plane are treated as lying on its inside, which is the section with the plane moved a
+    /// small distance towards its outside. Thus faces in the plane are part of the section if the
+    /// region is on the outside of the plane, and are not if it is on the inside.
+    ///
+    /// Returns `None` if the section can not be represented as a region, for example if it
+    /// consists of two parts touching at a vertex.
+    pub fn section(&self, plane: &Plane) -> Option<Region2D> {
+        Some(self.section_sourced(plane)?.0)
+    }
+
+    // The cross-section of the region with a plane, see `section`, together with the face plane
+    // each edge of the section lies on.
+    pub(crate) fn section_sourced(&self, plane: &Plane) -> Option<(Region2D, Vec<usize>)> {
+        let line_of: Vec<_> = self.planes.iter()
+            .map(|p| plane.intersection_line(p))
+            .collect();
+        let line_index: Vec<_> = line_of.iter()
+            .scan(0, |i, l| {
+                let index = l.map(|_| *i);
+                *i += l.is_some() as usize;
+                Some(index)
+            })
+            .collect();
+        let lines: Vec<Line> = line_of.iter().flatten().copied().collect();
+        let line_planes: Vec<_> = (0..line_of.len()).filter(|p| line_of[*p].is_some()).collect();
+
+        let positive = |p: na::Point3<f64>| plane.distance(p) >= -EPSILON;
+
+        // The edges of the section as the face they lie on and the face edges they start and
+        // end at.
+        let mut edges = Vec::new();
+        let mut starts = HashMap::new();
+
+        for (f, face) in self.faces.iter().enumerate() {
+            let Some(line) = line_of[face.plane] else {
+                continue
+            };
+
+            let face_plane = self.planes[face.plane];
+            let frame = face_plane.frame();
+            let dir = plane.frame() * na::Vector3::new(line.dir().x, line.dir().y, 0.0);
+
+            // The face edges crossing the plane, with their position along the section line and
+            // whether the face starts there.
+            let mut crossings = Vec::new();
+            for e in 0..face.region.edges().len() {
+                let (a, b) = self.edge_points(FaceEdge { face: f, edge: e });
+                if positive(a) == positive(b) {
+                    continue
+                }
+
+                let other = line_of[self.faces[face.links[e].face].plane]?;
+                let pos = line.intersection(&other)?.pos;
+
+                let normal = face.region.edge_line(e).normal();
+                let entry = (frame * na::Vector3::new(normal.x, normal.y, 0.0)).dot(&dir) > 0.0;
+                crossings.push((pos, entry, e));
+            }
+            crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            for (start, end) in pair_crossings(&crossings)? {
+                starts.insert((f, start), edges.len());
+                edges.push((f, start, end));
+            }
+        }
+
+        // Follow the section edges into loops through the linked face edges.
+        let mut visited = vec![false; edges.len()];
+        let mut loops = Vec::new();
+        for i in 0..edges.len() {
+            let mut edge_loop = Vec::new();
+            let mut j = i;
+            while !visited[j] {
+                visited[j] = true;
+                let (f, _, end) = edges[j];
+                edge_loop.push(line_index[self.faces[f].plane]?);
+
+                let link = self.faces[f].links[end];
+                j = *starts.get(&(link.face, link.edge))?;
+            }
+
+            if !edge_loop.is_empty() {
+                if j != i {
+                    return None
+                }
+
+                loops.push(edge_loop);
+            }
+        }
+
+        let (region, sources) = winding::resolve_sourced(&lines, &loops, |w| w >= 1)?;
+        Some((region, sources.into_iter().map(|l| line_planes[l]).collect()))
+    }
+
+    /// The volume of the region.
+    pub fn volume(&self) -> f64 {
+        // By the divergence theorem, as the outward normal of each face is the flipped plane
+        // normal and all points of a face have the same distance along it.
+        self.faces.iter()
+            .map(|f| self.planes[f.plane].distance(na::Point3::origin()) * f.region.area())
+            .sum::<f64>() / 3.0
+    }
+
+    /// Converts the region to a triangle mesh, with the triangles counterclockwise seen from the
+    /// outside.
+    ///
+    /// Vertices shared by several faces are merged, so the mesh is closed.
+    pub fn to_mesh(&self) -> Mesh {
+        let (vertices, corners) = self.vertices();
+        let mut faces = Vec::new();
+
+        for (f, face) in self.faces.iter().enumerate() {
+            let region = &face.region;
+            let triangulation = region.triangulate();
+
+            // The triangulation keeps the vertices of the region exactly.
+            let key = |p: na::Point2<f64>| [p.x.to_bits(), p.y.to_bits()];
+            let vertex_ids: HashMap<_, _> = (0..region.edges().len())
+                .map(|e| (key(region.edge_end_vertex(e)), corners[f][e]))
+                .collect();
+            let ids: Vec<_> = triangulation.points.iter()
+                .map(|p| vertex_ids[&key(*p)])
+                .collect();
+
+            // The triangles are counterclockwise seen from the inside of the plane.
+            faces.extend(triangulation.triangles.iter().map(|[a, b, c]| [ids[*a], ids[*c], ids[*b]]));
+        }
+
+        Mesh {
+            vertices: vertices.iter().map(|v| v.cast()).collect(),
+            faces,
+        }
+    }
+
+    // The vertices of the region, and the vertex at the end of each edge of each face.
+    //
+    // A vertex is identified by the sorted planes of the face and the linked faces of the two
+    // edges meeting there. Where more than three planes meet the faces give different planes for
+    // the same vertex, and these are joined through the links of the edges.
+    fn vertices(&self) -> (Vec<na::Point3<f64>>, Vec<Vec<usize>>) {
+        let mut ids = HashMap::new();
+        let mut corners: Vec<Vec<usize>> = Vec::new();
+
+        for face in &self.faces {
+            let region = &face.region;
+            let plane = |e: usize| self.faces[face.links[e].face].plane;
+            corners.push((0..region.edges().len())
+                .map(|e| {
+                    let mut v = [face.plane, plane(e), plane(region.edge_next(e))];
+                    v.sort();
+                    let n = ids.len();
+                    *ids.entry(v).or_insert(n)
+                })
+                .collect());
+        }
+
+        fn find(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+
+        // The end of an edge is the start of the linked edge, which is the end of the edge before
+        // it.
+        let mut parent: Vec<_> = (0..ids.len()).collect();
+        for (f, face) in self.faces.iter().enumerate() {
+            for (e, link) in face.links.iter().enumerate() {
+                let prev = self.faces[link.face].region.edge_prev(link.edge);
+                let (a, b) = (find(&mut parent, corners[f][e]), find(&mut parent, corners[link.face][prev]));
+                parent[a.max(b)] = a.min(b);
+            }
+        }
+
+        let mut index = HashMap::new();
+        let mut vertices = Vec::new();
+        for (f, face_corners) in corners.iter_mut().enumerate() {
+            for (e, v) in face_corners.iter_mut().enumerate() {
+                let root = find(&mut parent, *v);
+                *v = *index.entry(root).or_insert_with(|| {
+                    vertices.push(self.edge_points(FaceEdge { face: f, edge: e }).1);
+                    vertices.len() - 1
+                });
+            }
+        }
+
+        (vertices, corners)
+    }
+
+    fn check(&self) -> Option<()> {
+        for f in 0..self.faces.len() {
+            self.check_face(f)?;
+        }
+
+        for f in 0..self.faces.len() {
+            for g in f + 1..self.faces.len() {
+                self.check_faces_disjoint(f, g)?;
+            }
+        }
+
+        if !self.is_empty() && self.volume() <= 0.0 {
+            return None
+        }
+
+        Some(())
+    }
+
+    // Checks that a face is a proper node in the graph of faces.
+    //
+    // Each edge must be linked to an edge that is linked back, lie on the intersection line of the
+    // two face planes and go between the same vertices as the linked edge in the opposite
+    // direction.
+    fn check_face(&self, f: usize) -> Option<()> {
+        let face = &self.faces[f];
+        let plane = self.planes.get(face.plane)?;
+
+        if face.region.is_empty() || face.links.len() != face.region.edges().len() {
+            return None
+        }
+
+        for (e, link) in face.links.iter().enumerate() {
+            let edge = FaceEdge { face: f, edge: e };
+            let other = self.faces.get(link.face)?;
+
+            if link.face == f || other.links.get(link.edge) != Some(&edge) {
+                return None
+            }
+
+            let line = plane.intersection_line(self.planes.get(other.plane)?)?;
+            let edge_line = face.region.edge_line(e);
+            if !edge_line.coincident(&line) && !edge_line.coincident(&line.flip()) {
+                return None
+            }
+
+            let (a, b) = self.edge_points_safe(edge)?;
+            let (c, d) = self.edge_points_safe(*link)?;
+            if (a - d).norm() > EPSILON || (b - c).norm() > EPSILON {
+                return None
+            }
+
+            // The vertices of the face region must agree with the vertices from the planes.
+            let (a2, b2) = face.region.edge_points(e);
+            if (plane.to_global(a2) - a).norm() > EPSILON || (plane.to_global(b2) - b).norm() > EPSILON {
+                return None
+            }
+        }
+
+        Some(())
+    }
+
+    // Checks that two faces that are not neighbours only touch.
+    //
+    // The faces can only intersect along the intersection line of their planes, and so the cuts
+    // of the two faces along the line may not overlap.
+    fn check_faces_disjoint(&self, f: usize, g: usize) -> Option<()> {
+        if self.faces[f].links.iter().any(|l| l.face == g) {
+            return Some(())
+        }
+
+        let pf = self.face_plane(f);
+        let pg = self.face_plane(g);
+
+        let (Some(lf), Some(lg)) = (pf.intersection_line(&pg), pg.intersection_line(&pf)) else {
+            // Faces on parallel planes can only intersect if the planes are the same.
+            if pf.distance(pg.origin()).abs() > EPSILON {
+                return Some(())
+            }
+
+            // Faces on the same plane may touch but not overlap.
+            let a = &self.faces[f].region;
+            let b = self.faces[g].region.transform(plane_to_plane(&pg, &pf)).ok()?;
+            return (a.boolean(&b, BooleanOp::Intersection)?.area() <= EPSILON).then_some(())
+        };
+
+        let cut_f = self.faces[f].region.cut_region(lf);
+
+        // The cut of `g` as positions along the line in the frame of `f`.
+        let cut_g = self.faces[g].region.cut_region(lg);
+        let mut xs: Vec<_> = cut_g.points().iter()
+            .map(|x| lf.project_point(pf.to_local(pg.to_global(lg.point(x.pos)))).pos)
+            .collect();
+        xs.sort_by(f64::total_cmp);
+        let cut_g = Region1D::new(xs.chunks(2)
+            .flat_map(|x| [LinePoint::new(x[0], true), LinePoint::new(x[1], false)])
+            .collect())?;
+
+        let (overlap, _) = cut_f.split(&cut_g);
+        let points = overlap.points();
+        if (0..points.len()).step_by(2).any(|i| points[i + 1].pos - points[i].pos > EPSILON) {
+            return None
+        }
+
+        Some(())
+    }
+}
+
+// Pairs the crossings of a line with the boundary of a face, sorted by position, into the parts of
+// the line in the face.
+//
+// Crossings within `EPSILON` of each other are at the same point, and are ordered so that entries
+// and exits alternate.
+fn pair_crossings(crossings: &[(f64, bool, usize)]) -> Option<Vec<(usize, usize)>> {
+    let mut ordered = Vec::new();
+    let mut inside = false;
+    let mut i = 0;
+    while i < crossings.len() {
+        let group = crossings[i..].iter()
+            .take_while(|c| c.0 - crossings[i].0 <= EPSILON)
+            .count();
+
+        let (mut entries, mut exits): (Vec<&(f64, bool, usize)>, Vec<_>) = crossings[i..i + group].iter()
+            .partition(|c| c.1);
+        while !entries.is_empty() || !exits.is_empty() {
+            let next = if inside { exits.pop() } else { entries.pop() };
+            ordered.push(next?.2);
+            inside = !inside;
+        }
+
+        i += group;
+    }
+
+    if inside {
+        return None
+    }
+
+    Some(ordered.chunks(2).map(|c| (c[0], c[1])).collect())
+}
+
+// A face in the plane `p` bounded by loops of neighbouring planes, where the lines of the face are
+// the intersections of the plane with the neighbouring planes. The direction of each line is
+// given by its vertices, the intersections with the neighbouring planes before and after it.
+//
+// Returns the region of the face and the neighbouring plane of each line of the region.
+pub(crate) fn face_from_planes(planes: &[Plane], p: usize, loops: &[Vec<usize>]) -> Option<(Region2D, Vec<usize>)> {
+    let mut lines = Vec::new();
+    let mut line_planes = Vec::new();
+    let mut face_loops = Vec::new();
+
+    for l in loops {
+        let m = l.len();
+        face_loops.push((lines.len()..lines.len() + m).collect());
+        for i in 0..m {
+            let (prev, q, next) = (&planes[l[(i + m - 1) % m]], &planes[l[i]], &planes[l[(i + 1) % m]]);
+            let a = Plane::intersection_point([&planes[p], prev, q])?;
+            let b = Plane::intersection_point([&planes[p], q, next])?;
+
+            // The face is on the outside of the neighbouring plane at reflex edges, so the line
+            // follows the direction of the edge.
+            let line = planes[p].intersection_line(q)?;
+            let dir = planes[p].to_local(b) - planes[p].to_local(a);
+            lines.push(if line.dir().dot(&dir) < 0.0 { line.flip() } else { line });
+            line_planes.push(l[i]);
+        }
+    }
+
+    Some((Region2D::from_loops(lines, &face_loops)?, line_planes))
+}
+
+// The transformation from the local frame of one plane to the local frame of another plane.
+fn plane_to_plane(from: &Plane, to: &Plane) -> na::Matrix3<f64> {
+    let t = to.frame().inverse() * from.frame();
+    let m = t.to_homogeneous();
+    na::Matrix3::new(
+        m[(0, 0)], m[(0, 1)], m[(0, 3)],
+        m[(1, 0)], m[(1, 1)], m[(1, 3)],
+        0.0, 0.0, 1.0,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{closed, cuboid_polyhedron, l_prism};
+
+    #[test]
+    fn cuboid_valid() {
+        let (vertices, faces) = cuboid_polyhedron([0.0; 3], [1.0, 2.0, 3.0]);
+        let region = Region3D::from_polyhedron(&vertices, &faces).unwrap();
+        assert_eq!(region.faces().len(), 6);
+        assert!((region.volume() - 6.0).abs() < 1e-9);
+
+        for (f, face) in region.faces().iter().enumerate() {
+            assert_eq!(face.links.len(), 4);
+            for e in 0..4 {
+                let (a, b) = region.edge_points(FaceEdge { face: f, edge: e });
+                assert!(vertices.iter().any(|v| (v - a).norm() < 1e-9));
+                assert!(vertices.iter().any(|v| (v - b).norm() < 1e-9));
+            }
+        }
+    }
+
+    #[test]
+    fn inverted_cuboid_invalid() {
+        let (vertices, faces) = cuboid_polyhedron([0.0; 3], [1.0, 1.0, 1.0]);
+        let faces: Vec<_> = faces.into_iter()
+            .map(|f| f.into_iter().map(|l| l.into_iter().rev().collect()).collect())
+            .collect();
+        assert!(Region3D::from_polyhedron(&vertices, &faces).is_none());
+    }
+
+    #[test]
+    fn open_cuboid_invalid() {
+        let (vertices, mut faces) = cuboid_polyhedron([0.0; 3], [1.0, 1.0, 1.0]);
+        faces.pop();
+        assert!(Region3D::from_polyhedron(&vertices, &faces).is_none());
+    }
+
+    #[test]
+    fn l_prism_valid() {
+        // An L shaped prism, which has a reflex edge.
+        let region = l_prism();
+        assert_eq!(region.faces().len(), 8);
+        assert!((region.volume() - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn pyramid_mesh() {
+        // Four faces meet at the apex, which is identified through the links of the edges.
+        let vertices = [(0.0, 0.0, 0.0), (1.0, 0.0, 0.0), (0.0, 1.0, 0.0), (1.0, 1.0, 0.0), (0.5, 0.5, 1.0)]
+            .map(|(x, y, z)| na::Point3::new(x, y, z));
+        let faces = [vec![0, 2, 3, 1], vec![0, 1, 4], vec![1, 3, 4], vec![3, 2, 4], vec![2, 0, 4]]
+            .map(|f| vec![f]);
+
+        let region = Region3D::from_polyhedron(&vertices, &faces).unwrap();
+        assert!((region.volume() - 1.0 / 3.0).abs() < 1e-9);
+
+        let mesh = region.to_mesh();
+        assert_eq!(mesh.vertices.len(), 5);
+        assert_eq!(mesh.faces.len(), 6);
+        assert!(closed(&mesh));
+    }
+
+    #[test]
+    fn cut_cuboid_stable() {
+        for scale in [1e-2, 1.0, 1e9] {
+            let (vertices, faces) = cuboid_polyhedron([0.0; 3], [scale; 3]);
+            let region = Region3D::from_polyhedron(&vertices, &faces).unwrap();
+            let point = |x: f64, y: f64, z: f64| na::Point3::new(x, y, z) * scale;
+
+            let cut = region.cut_region(Line3D::from_point_dir(point(0.5, 0.5, -1.0), na::Vector3::z()));
+            assert_eq!(cut.points().len(), 2);
+            assert!((cut.points()[0].pos - scale).abs() < 1e-9 * scale);
+            assert!((cut.points()[1].pos - 2.0 * scale).abs() < 1e-9 * scale);
+
+            // The cut along a line is the part of the line in the closed cube, or empty if the
+            // line only touches the cube.
+            let chord = |line: &Line3D| {
+                let (o, d) = (line.origin() / scale, line.dir().into_inner());
+                let (mut t0, mut t1) = (f64::NEG_INFINITY, f64::INFINITY);
+                for i in 0..3 {
+                    if d[i] != 0.0 {
+                        let (a, b) = (-o[i] / d[i], (1.0 - o[i]) / d[i]);
+                        t0 = t0.max(a.min(b));
+                        t1 = t1.min(a.max(b));
+                    }
+                }
+                (t0 * scale, t1 * scale)
+            };
+            let exact = |line: &Line3D| {
+                let (t0, t1) = chord(line);
+                let cut = region.cut_region(*line);
+                let points = cut.points();
+                points.len() == 2
+                    && (points[0].pos - t0).abs() < 1e-9 * scale
+                    && (points[1].pos - t1).abs() < 1e-9 * scale
+            };
+
+            for i in 1..200 {
+                let (sin, cos) = (i as f64 * std::f64::consts::FRAC_PI_2 / 200.0).sin_cos();
+                let slope = (i as f64 * 0.37).sin();
+
+                // Lines through an edge and through a vertex, going through the region.
+                let through_edge = Line3D::from_point_dir(point(0.0, 0.0, 0.5), na::Vector3::new(cos, sin, slope));
+                let through_vertex = Line3D::from_point_dir(point(0.0, 0.0, 0.0), na::Vector3::new(cos, sin, slope.abs() + 0.1));
+                assert!(exact(&through_edge));
+                assert!(exact(&through_vertex));
+
+                // Lines touching an edge or a vertex, or lying in a face.
+                let touching_edge = Line3D::from_point_dir(point(0.0, 0.0, 0.5), na::Vector3::new(-cos, sin, slope));
+                let touching_vertex = Line3D::from_point_dir(point(1.0, 1.0, 1.0), na::Vector3::new(cos, -sin, slope));
+                let in_face = Line3D::from_point_dir(point(0.5, 0.5, 1.0), na::Vector3::new(cos, sin, 0.0));
+                let in_face_vertex = Line3D::from_point_dir(point(1.0, 1.0, 1.0), na::Vector3::new(cos, -sin, 0.0));
+                for line in [touching_edge, touching_vertex, in_face, in_face_vertex] {
+                    assert!(region.cut_region(line).points().is_empty() || exact(&line));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn inside_l_prism() {
+        let region = l_prism();
+
+        assert!(region.inside(na::Point3::new(0.5, 1.5, 0.5)));
+        assert!(region.inside(na::Point3::new(1.5, 0.5, 0.5)));
+        assert!(!region.inside(na::Point3::new(1.5, 1.5, 0.5)));
+        assert!(!region.inside(na::Point3::new(0.5, 0.5, 1.5)));
+        // Rays along a face and through the reflex edge.
+        assert!(!region.inside(na::Point3::new(-0.5, 1.0, 0.5)));
+        assert!(region.inside(na::Point3::new(0.5, 1.0, 0.5)));
+        assert!(region.inside(na::Point3::new(0.5, 1.0, 1.0 - 1e-3)));
+        assert!(!region.inside(na::Point3::new(0.5, 1.0, 1.0 + 1e-3)));
+    }
+
+    #[test]
+    fn cuboid_sections() {
+        let (vertices, faces) = cuboid_polyhedron([0.0; 3], [1.0, 1.0, 1.0]);
+        let region = Region3D::from_polyhedron(&vertices, &faces).unwrap();
+        let section = |p: na::Point3<f64>, n: na::Vector3<f64>| {
+            let plane = Plane::from_point_normal(p, na::Unit::new_normalize(n));
+            region.section(&plane).unwrap()
+        };
+
+        let middle = section(na::Point3::new(0.0, 0.0, 0.5), na::Vector3::z());
+        assert_eq!(middle.edges().len(), 4);
+        assert!((middle.area() - 1.0).abs() < 1e-9);
+
+        // A plane containing a face, from both sides.
+        assert!((section(na::Point3::new(0.0, 0.0, 1.0), na::Vector3::z()).area() - 1.0).abs() < 1e-9);
+        assert!(section(na::Point3::new(0.0, 0.0, 1.0), -na::Vector3::z()).is_empty());
+        assert!(section(na::Point3::new(0.0, 0.0, 2.0), na::Vector3::z()).is_empty());
+
+        // Planes through edges and vertices.
+        let diagonal = section(na::Point3::new(1.0, 0.0, 0.0), na::Vector3::new(1.0, 1.0, 0.0));
+        assert!((diagonal.area() - 2f64.sqrt()).abs() < 1e-9);
+        let corner = section(na::Point3::new(1.0, 0.0, 0.0), na::Vector3::new(1.0, 1.0, 1.0));
+        assert_eq!(corner.edges().len(), 3);
+        assert!((corner.area() - 3f64.sqrt() / 2.0).abs() < 1e-9);
+        assert!(section(na::Point3::new(1.0, 1.0, 1.0), na::Vector3::new(1.0, 1.0, 1.0)).is_empty());
+        assert!(section(na::Point3::new(1.0, 1.0, 1.0), -na::Vector3::new(1.0, 1.0, 1.0)).area() < 1e-9);
+
+        let plane = Plane::from_point_normal(na::Point3::new(0.0, 0.0, 0.5), na::Vector3::z_axis());
+        for v in middle.loop_vertices().into_iter().flatten() {
+            let p = plane.to_global(v);
+            assert!(p.x.abs() < 1e-9 || (p.x - 1.0).abs() < 1e-9 || p.y.abs() < 1e-9 || (p.y - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn l_prism_sections() {
+        let region = l_prism();
+
+        let plane = Plane::from_point_normal(na::Point3::new(0.0, 0.0, 0.5), na::Vector3::z_axis());
+        let section = region.section(&plane).unwrap();
+        assert_eq!(section.edges().len(), 6);
+        assert!((section.area() - 3.0).abs() < 1e-9);
+        assert!(section.inside(plane.to_local(na::Point3::new(0.5, 1.5, 0.5))));
+        assert!(!section.inside(plane.to_local(na::Point3::new(1.5, 1.5, 0.5))));
+
+        // A plane containing the face at the reflex edge.
+        let plane = Plane::from_point_normal(na::Point3::new(0.0, 1.0, 0.0), na::Vector3::y_axis());
+        assert!((region.section(&plane).unwrap().area() - 2.0).abs() < 1e-9);
+        assert!((region.section(&plane.flip()).unwrap().area() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn intersecting_faces_invalid() {
+        // Two cubes overlapping in a corner.
+        let (mut vertices, mut faces) = cuboid_polyhedron([0.0; 3], [2.0, 2.0, 2.0]);
+        let (v2, f2) = cuboid_polyhedron([0.0; 3], [2.0, 2.0, 2.0]);
+        vertices.extend(v2.iter().map(|v| v + na::Vector3::new(1.0, 1.0, 1.0)));
+        faces.extend(f2.into_iter().map(|f| f.into_iter().map(|l| l.into_iter().map(|i| i + 8).collect()).collect()));
+
+        assert!(Region3D::from_polyhedron(&vertices, &faces).is_none());
+
+        // Apart they are valid, also with the bottom faces on the same plane.
+        let (mut vertices, mut faces) = cuboid_polyhedron([0.0; 3], [1.0, 1.0, 1.0]);
+        let (v2, f2) = cuboid_polyhedron([0.0; 3], [1.0, 1.0, 1.0]);
+        vertices.extend(v2.iter().map(|v| v + na::Vector3::new(2.0, 0.5, 0.0)));
+        faces.extend(f2.into_iter().map(|f| f.into_iter().map(|l| l.into_iter().map(|i| i + 8).collect()).collect()));
+
+        let region = Region3D::from_polyhedron(&vertices, &faces).unwrap();
+        assert!((region.volume() - 2.0).abs() < 1e-9);
+    }
+}
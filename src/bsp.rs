@@ -0,0 +1,469 @@
+//! Binary space partitioning trees of regions.
+//!
+//! Each node of a tree splits the plane by a line into the inside and the outside of the line,
+//! and each leaf is either inside or outside the region. A tree is built from the edges of a
+//! region by splitting the plane by the line of one edge and partitioning the other edges to the
+//! two sides.
+//!
+//! Boolean operations merge the two trees. The nodes of the first tree are kept and the second
+//! tree is restricted to each leaf of the first tree, where the leaf decides the result together
+//! with the restricted tree. To restrict a tree to a part of the plane the convex cell of each node
+//! is tracked, and nodes whose line does not cross the cell are skipped.
+//!
+//! A tree is converted back to a region by pushing each edge of each inside leaf cell down the
+//! tree. The parts of the edge that reach an outside leaf on the far side of the edge are on the
+//! boundary of the region, and the node line they lie on is the line of their edge.
+
+use crate::brep_2d::Region2D;
+use crate::geometry::{Line, EPSILON};
+use crate::relation::BooleanOp;
+use crate::winding;
+
+/// A binary space partitioning tree of a region.
+#[derive(Clone, Debug)]
+pub enum BspTree {
+    /// A part of the plane inside the region.
+    In,
+    /// A part of the plane outside the region.
+    Out,
+    /// A split of the plane by a line.
+    Node {
+        line: Line,
+        /// The tree on the inside of the line.
+        inside: Box<BspTree>,
+        /// The tree on the outside of the line.
+        outside: Box<BspTree>,
+    },
+}
+
+/// The position of a point relative to a region.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PointClass {
+    Inside,
+    Outside,
+    Boundary,
+}
+
+// A part of an edge, with end points on the line.
+#[derive(Copy, Clone)]
+struct Segment {
+    line: Line,
+    p1: na::Point2<f64>,
+    p2: na::Point2<f64>,
+}
+
+impl BspTree {
+    /// Builds a tree from the edges of a region.
+    pub fn from_region(region: &Region2D) -> Self {
+        let segments = (0..region.edges().len())
+            .map(|e| {
+                let (p1, p2) = region.edge_points(e);
+                Segment {
+                    line: region.edge_line(e),
+                    p1,
+                    p2,
+                }
+            })
+            .collect();
+
+        build(segments, BspTree::Out)
+    }
+
+    /// Converts the tree to a region, the union of the leaf cells inside the region.
+    ///
+    /// Returns `None` if the tree is unbounded or the inside can not be represented as a region.
+    pub fn to_region(&self) -> Option<Region2D> {
+        let mut cells = Vec::new();
+        self.inside_cells(bounding_box(bounding_size(&[self])), &mut cells);
+
+        let mut fragments = Vec::new();
+        for cell in &cells {
+            for i in 0..cell.len() {
+                let (p1, p2) = (cell[i], cell[(i + 1) % cell.len()]);
+                let d = p2 - p1;
+                if d.norm() > EPSILON {
+                    // Cells are clockwise, so the outside of the cell is on the left.
+                    self.boundary_fragments(p1, p2, na::Vector2::new(-d.y, d.x), None, &mut fragments);
+                }
+            }
+        }
+
+        // Fragments on the bounding box are not on any node line, and the region is unbounded.
+        let fragments = fragments.into_iter()
+            .map(|(p1, p2, line)| Some((p1, p2, line?)))
+            .collect::<Option<Vec<_>>>()?;
+
+        let points: Vec<_> = fragments.iter().flat_map(|(p1, p2, _)| [*p1, *p2]).collect();
+        let (nodes, ids) = winding::merge_points(&points);
+        let links: Vec<_> = ids.chunks(2).map(|c| (c[0], c[1])).collect();
+
+        let lines = fragments.iter().map(|f| f.2).collect();
+        let loops = winding::link_boundary(&nodes, &links)?;
+        Region2D::from_loops(lines, &loops)?.simplify()
+    }
+
+    /// Classifies a point as inside, outside or on the boundary of the region.
+    pub fn classify(&self, point: na::Point2<f64>) -> PointClass {
+        match self {
+            BspTree::In => PointClass::Inside,
+            BspTree::Out => PointClass::Outside,
+            BspTree::Node { line, inside, outside } => {
+                let dist = line.project_point(point).dist;
+                if dist > EPSILON {
+                    inside.classify(point)
+                } else if dist < -EPSILON {
+                    outside.classify(point)
+                } else {
+                    // On the line the point is on the boundary unless both sides agree.
+                    let a = inside.classify(point);
+                    let b = outside.classify(point);
+                    if a == b {
+                        a
+                    } else {
+                        PointClass::Boundary
+                    }
+                }
+            }
+        }
+    }
+
+    /// The tree of the complement of the region.
+    pub fn complement(&self) -> BspTree {
+        match self {
+            BspTree::In => BspTree::Out,
+            BspTree::Out => BspTree::In,
+            BspTree::Node { line, inside, outside } => BspTree::Node {
+                line: *line,
+                inside: Box::new(inside.complement()),
+                outside: Box::new(outside.complement()),
+            },
+        }
+    }
+
+    /// Computes a boolean operation of two trees.
+    pub fn boolean(&self, other: &BspTree, op: BooleanOp) -> BspTree {
+        let cell = bounding_box(bounding_size(&[self, other]));
+        merge(self, other, op, &cell)
+    }
+
+    // The leaf cells inside the region, as clockwise polygons.
+    fn inside_cells(&self, cell: Vec<na::Point2<f64>>, cells: &mut Vec<Vec<na::Point2<f64>>>) {
+        if cell.len() < 3 {
+            return
+        }
+
+        match self {
+            BspTree::In => cells.push(cell),
+            BspTree::Out => (),
+            BspTree::Node { line, inside, outside } => {
+                inside.inside_cells(clip_cell(&cell, *line), cells);
+                outside.inside_cells(clip_cell(&cell, line.flip()), cells);
+            }
+        }
+    }
+
+    // Finds the parts of the segment from `p1` to `p2` with an outside leaf on the side of
+    // `normal`, together with the node line the part lies on, directed with the inside on the
+    // side away from `normal`.
+    fn boundary_fragments(
+        &self,
+        p1: na::Point2<f64>,
+        p2: na::Point2<f64>,
+        normal: na::Vector2<f64>,
+        on: Option<Line>,
+        fragments: &mut Vec<(na::Point2<f64>, na::Point2<f64>, Option<Line>)>,
+    ) {
+        match self {
+            BspTree::In => (),
+            BspTree::Out => fragments.push((p1, p2, on)),
+            BspTree::Node { line, inside, outside } => {
+                let d1 = line.project_point(p1).dist;
+                let d2 = line.project_point(p2).dist;
+
+                if d1.abs() <= EPSILON && d2.abs() <= EPSILON {
+                    let towards_inside = line.normal().dot(&normal) > 0.0;
+                    let on = on.or(Some(if towards_inside { line.flip() } else { *line }));
+                    let side = if towards_inside { inside } else { outside };
+                    side.boundary_fragments(p1, p2, normal, on, fragments);
+                } else if d1 >= -EPSILON && d2 >= -EPSILON {
+                    inside.boundary_fragments(p1, p2, normal, on, fragments);
+                } else if d1 <= EPSILON && d2 <= EPSILON {
+                    outside.boundary_fragments(p1, p2, normal, on, fragments);
+                } else {
+                    let p = p1 + (p2 - p1) * (d1 / (d1 - d2));
+                    let (a, b) = if d1 > 0.0 { (inside, outside) } else { (outside, inside) };
+                    a.boundary_fragments(p1, p, normal, on, fragments);
+                    b.boundary_fragments(p, p2, normal, on, fragments);
+                }
+            }
+        }
+    }
+
+    fn lines(&self, lines: &mut Vec<Line>) {
+        if let BspTree::Node { line, inside, outside } = self {
+            lines.push(*line);
+            inside.lines(lines);
+            outside.lines(lines);
+        }
+    }
+}
+
+// Builds a tree from segments, where `leaf` is used for sides without segments.
+fn build(mut segments: Vec<Segment>, leaf: BspTree) -> BspTree {
+    if segments.is_empty() {
+        return leaf
+    }
+
+    let line = segments.swap_remove(0).line;
+    let mut inside = Vec::new();
+    let mut outside = Vec::new();
+
+    for s in segments {
+        let d1 = line.project_point(s.p1).dist;
+        let d2 = line.project_point(s.p2).dist;
+
+        if d1.abs() <= EPSILON && d2.abs() <= EPSILON {
+            // Edges on the line are represented by the node.
+            continue
+        } else if d1 >= -EPSILON && d2 >= -EPSILON {
+            inside.push(s);
+        } else if d1 <= EPSILON && d2 <= EPSILON {
+            outside.push(s);
+        } else {
+            let p = s.p1 + (s.p2 - s.p1) * (d1 / (d1 - d2));
+            let (a, b) = (Segment { p2: p, ..s }, Segment { p1: p, ..s });
+            if d1 > 0.0 {
+                inside.push(a);
+                outside.push(b);
+            } else {
+                inside.push(b);
+                outside.push(a);
+            }
+        }
+    }
+
+    BspTree::Node {
+        line,
+        inside: Box::new(build(inside, BspTree::In)),
+        outside: Box::new(build(outside, BspTree::Out)),
+    }
+}
+
+fn merge(a: &BspTree, b: &BspTree, op: BooleanOp, cell: &[na::Point2<f64>]) -> BspTree {
+    match (op, a, b) {
+        (BooleanOp::Union, BspTree::In, _) | (BooleanOp::Union, _, BspTree::In) => BspTree::In,
+        (BooleanOp::Union, BspTree::Out, b) => restrict(b, cell),
+        (BooleanOp::Union, a, BspTree::Out) => restrict(a, cell),
+        (BooleanOp::Intersection, BspTree::Out, _) | (BooleanOp::Intersection, _, BspTree::Out) => BspTree::Out,
+        (BooleanOp::Intersection, BspTree::In, b) => restrict(b, cell),
+        (BooleanOp::Intersection, a, BspTree::In) => restrict(a, cell),
+        (BooleanOp::Difference, BspTree::Out, _) | (BooleanOp::Difference, _, BspTree::In) => BspTree::Out,
+        (BooleanOp::Difference, BspTree::In, b) => restrict(b, cell).complement(),
+        (BooleanOp::Difference, a, BspTree::Out) => restrict(a, cell),
+        (_, BspTree::Node { line, inside, outside }, b) => {
+            let cell_in = clip_cell(cell, *line);
+            let cell_out = clip_cell(cell, line.flip());
+            node(
+                *line,
+                merge(inside, &restrict(b, &cell_in), op, &cell_in),
+                merge(outside, &restrict(b, &cell_out), op, &cell_out),
+            )
+        }
+    }
+}
+
+// The tree restricted to a convex cell, skipping nodes whose line does not cross the cell.
+fn restrict(tree: &BspTree, cell: &[na::Point2<f64>]) -> BspTree {
+    match tree {
+        BspTree::In | BspTree::Out => tree.clone(),
+        BspTree::Node { line, inside, outside } => {
+            let dists: Vec<_> = cell.iter().map(|p| line.project_point(*p).dist).collect();
+            if dists.iter().all(|d| *d >= -EPSILON) {
+                restrict(inside, cell)
+            } else if dists.iter().all(|d| *d <= EPSILON) {
+                restrict(outside, cell)
+            } else {
+                node(
+                    *line,
+                    restrict(inside, &clip_cell(cell, *line)),
+                    restrict(outside, &clip_cell(cell, line.flip())),
+                )
+            }
+        }
+    }
+}
+
+// A node, or a leaf if both sides are the same leaf.
+fn node(line: Line, inside: BspTree, outside: BspTree) -> BspTree {
+    match (&inside, &outside) {
+        (BspTree::In, BspTree::In) => BspTree::In,
+        (BspTree::Out, BspTree::Out) => BspTree::Out,
+        _ => BspTree::Node {
+            line,
+            inside: Box::new(inside),
+            outside: Box::new(outside),
+        },
+    }
+}
+
+// The part of a convex polygon on the inside of a line.
+fn clip_cell(cell: &[na::Point2<f64>], line: Line) -> Vec<na::Point2<f64>> {
+    let mut clipped = Vec::new();
+
+    for i in 0..cell.len() {
+        let (p, q) = (cell[i], cell[(i + 1) % cell.len()]);
+        let dp = line.project_point(p).dist;
+        let dq = line.project_point(q).dist;
+
+        if dp >= 0.0 {
+            clipped.push(p);
+        }
+
+        if (dp >= 0.0) != (dq >= 0.0) {
+            clipped.push(p + (q - p) * (dp / (dp - dq)));
+        }
+    }
+
+    clipped
+}
+
+// A size such that the box from `-size` to `size` contains the intersections and origins of all
+// lines of the trees.
+fn bounding_size(trees: &[&BspTree]) -> f64 {
+    let mut lines = Vec::new();
+    for tree in trees {
+        tree.lines(&mut lines);
+    }
+
+    1.0 + lines.iter()
+        .enumerate()
+        .flat_map(|(i, a)| {
+            lines[i + 1..].iter()
+                .filter_map(|b| a.intersection_point(b))
+                .chain([a.origin()])
+        })
+        .map(|p| p.coords.amax())
+        .fold(0.0, f64::max)
+        * 2.0
+}
+
+fn bounding_box(size: f64) -> Vec<na::Point2<f64>> {
+    [(-1.0, -1.0), (-1.0, 1.0), (1.0, 1.0), (1.0, -1.0)]
+        .map(|(x, y)| na::Point2::new(x * size, y * size))
+        .to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{l_shape, rect};
+
+    #[test]
+    fn bsp_classify_and_convert() {
+        let region = l_shape();
+        let tree = BspTree::from_region(&region);
+
+        assert_eq!(tree.classify(na::Point2::new(0.5, 2.5)), PointClass::Inside);
+        assert_eq!(tree.classify(na::Point2::new(2.5, 0.5)), PointClass::Inside);
+        assert_eq!(tree.classify(na::Point2::new(2.0, 2.0)), PointClass::Outside);
+        assert_eq!(tree.classify(na::Point2::new(-1.0, 0.5)), PointClass::Outside);
+        assert_eq!(tree.classify(na::Point2::new(0.0, 1.5)), PointClass::Boundary);
+        assert_eq!(tree.classify(na::Point2::new(1.0, 1.0)), PointClass::Boundary);
+
+        let back = tree.to_region().unwrap();
+        assert_eq!(back.edges().len(), 6);
+        assert!((back.area() - region.area()).abs() < 1e-9);
+
+        assert!(tree.complement().to_region().is_none());
+        assert!(BspTree::from_region(&Region2D::empty()).to_region().unwrap().is_empty());
+    }
+
+    #[test]
+    fn bsp_booleans() {
+        // The areas of the union, intersection and difference, and points in both regions, only
+        // the first, only the second and neither.
+        let cases = [
+            (
+                rect(0.0, 0.0, 2.0, 2.0),
+                rect(1.0, 1.0, 3.0, 3.0),
+                [7.0, 1.0, 3.0],
+                [(1.5, 1.5), (0.5, 0.5), (2.5, 2.5), (2.5, 0.5)],
+            ),
+            (
+                l_shape(),
+                rect(0.5, 0.5, 2.0, 2.0),
+                [6.0, 1.25, 3.75],
+                [(0.75, 1.5), (0.25, 0.25), (1.75, 1.75), (2.5, 2.5)],
+            ),
+        ];
+
+        for (a, b, areas, points) in &cases {
+            let (ta, tb) = (BspTree::from_region(a), BspTree::from_region(b));
+            let ops = [BooleanOp::Union, BooleanOp::Intersection, BooleanOp::Difference];
+
+            for (op, area) in ops.into_iter().zip(areas) {
+                let tree = ta.boolean(&tb, op);
+                let region = tree.to_region().unwrap();
+                assert!((region.area() - area).abs() < 1e-9, "{:?}", op);
+
+                for (i, (x, y)) in points.iter().enumerate() {
+                    let p = na::Point2::new(*x, *y);
+                    let (in_a, in_b) = (i < 2, i % 2 == 0);
+                    let expected = match op {
+                        BooleanOp::Union => in_a || in_b,
+                        BooleanOp::Intersection => in_a && in_b,
+                        BooleanOp::Difference => in_a && !in_b,
+                    };
+                    let class = if expected { PointClass::Inside } else { PointClass::Outside };
+                    assert_eq!(tree.classify(p), class, "{:?} {}", op, p);
+                    assert_eq!(region.inside(p), expected, "{:?} {}", op, p);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bsp_booleans_match_region_booleans() {
+        let ring = Region2D::from_polygons(&[
+            vec![
+                na::Point2::new(-1.0, -1.0),
+                na::Point2::new(-1.0, 2.0),
+                na::Point2::new(2.0, 2.0),
+                na::Point2::new(2.0, -1.0),
+            ],
+            vec![
+                na::Point2::new(1.5, -0.5),
+                na::Point2::new(1.5, 0.5),
+                na::Point2::new(0.5, 0.5),
+                na::Point2::new(0.5, -0.5),
+            ],
+        ]).unwrap();
+        let pairs = [
+            (rect(0.0, 0.0, 2.0, 2.0), rect(1.0, 1.0, 3.0, 3.0)),
+            (l_shape(), rect(0.5, 0.5, 2.0, 2.0)),
+            (ring, l_shape()),
+            (rect(0.0, 0.0, 1.0, 1.0), rect(2.0, 2.0, 3.0, 3.0)),
+        ];
+
+        for (a, b) in &pairs {
+            let ta = BspTree::from_region(a);
+            let tb = BspTree::from_region(b);
+
+            for op in [BooleanOp::Union, BooleanOp::Intersection, BooleanOp::Difference] {
+                let expected = a.boolean(b, op).unwrap();
+                let tree = ta.boolean(&tb, op);
+                let region = tree.to_region().unwrap();
+                assert!((region.area() - expected.area()).abs() < 1e-9, "{:?}", op);
+                assert_eq!(region.loops().len(), expected.loops().len());
+
+                for x in 0..8 {
+                    for y in 0..8 {
+                        let p = na::Point2::new(x as f64 * 0.5 - 0.75, y as f64 * 0.5 - 0.75);
+                        let inside = tree.classify(p) == PointClass::Inside;
+                        assert_eq!(inside, expected.inside(p), "{:?} {}", op, p);
+                    }
+                }
+            }
+        }
+    }
+}
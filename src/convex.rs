@@ -0,0 +1,291 @@
+use std::collections::VecDeque;
+
+use crate::brep_2d::Region2D;
+use crate::geometry::{Line, EPSILON};
+use crate::predicates::orient2d;
+
+impl Region2D {
+    /// Creates the convex region that is the intersection of the insides of a set of lines.
+    ///
+    /// Returns an empty region if the intersection is empty and `None` if it is unbounded. Runs in
+    /// O(n log n) time.
+    pub fn from_half_planes(lines: &[Line]) -> Option<Self> {
+        let mut lines: Vec<_> = lines.to_vec();
+        lines.sort_by(|a, b| angle(a).total_cmp(&angle(b)));
+
+        // Of parallel lines with the same direction only the innermost one matters.
+        lines.dedup_by(|a, b| {
+            if same_direction(a, b) {
+                if a.project_point(b.origin()).dist < 0.0 {
+                    *b = *a;
+                }
+                true
+            } else {
+                false
+            }
+        });
+
+        while lines.len() > 1 && same_direction(&lines[0], &lines[lines.len() - 1]) {
+            let last = lines.pop().unwrap();
+            if last.project_point(lines[0].origin()).dist < 0.0 {
+                lines[0] = last;
+            }
+        }
+
+        let (i, gap) = largest_gap(&lines)?;
+        if gap >= std::f64::consts::PI - EPSILON {
+            // All normals point to the same side of the direction in the middle of the gap, so
+            // points far enough in that direction are inside every line. Only the lines on both
+            // sides of a gap of exactly half a turn can not be satisfied together, as they face
+            // away from each other.
+            if gap > std::f64::consts::PI + EPSILON {
+                return None
+            }
+
+            let (a, b) = (lines[i], lines[(i + 1) % lines.len()]);
+            return if a.project_point(b.origin()).dist > EPSILON {
+                None
+            } else {
+                Some(Self::empty())
+            }
+        }
+
+        let boundary = intersect(&lines).unwrap_or_default();
+        if boundary.len() < 3 {
+            return Some(Self::empty())
+        }
+
+        // The lines are sorted counterclockwise, while the boundary of a region goes clockwise.
+        let n = boundary.len();
+        let lines = boundary.into_iter().rev().collect();
+
+        // Intersections that are too thin to be a valid region are considered empty.
+        Some(Self::from_loops(lines, &[(0..n).collect()]).unwrap_or_else(Self::empty))
+    }
+
+    /// Creates the convex hull of a set of points.
+    ///
+    /// Collinear points on the hull are not included as vertices. Returns an empty region if all
+    /// points are collinear.
+    pub fn from_convex_hull(points: &[na::Point2<f64>]) -> Self {
+        let mut points = points.to_vec();
+        points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+        points.dedup();
+
+        // Andrew's monotone chain, building the lower and the upper hull counterclockwise.
+        let chain = |points: &mut dyn Iterator<Item = &na::Point2<f64>>| {
+            let mut chain: Vec<na::Point2<f64>> = Vec::new();
+            for p in points {
+                while chain.len() >= 2 && orient2d(chain[chain.len() - 2], chain[chain.len() - 1], *p) <= 0.0 {
+                    chain.pop();
+                }
+                chain.push(*p);
+            }
+            // The last point is the first point of the other chain.
+            chain.pop();
+            chain
+        };
+
+        let mut hull = chain(&mut points.iter());
+        hull.extend(chain(&mut points.iter().rev()));
+
+        if hull.len() < 3 {
+            return Self::empty()
+        }
+
+        // Regions have their inside on the right, so the boundary goes clockwise.
+        hull.reverse();
+
+        // Hulls that are too thin to be a valid region are considered empty.
+        Self::from_polygons(&[hull]).unwrap_or_else(Self::empty)
+    }
+
+    /// The convex hull of the region.
+    pub fn convex_hull(&self) -> Self {
+        let vertices: Vec<_> = self.loop_vertices().into_iter().flatten().collect();
+        Self::from_convex_hull(&vertices)
+    }
+}
+
+fn angle(line: &Line) -> f64 {
+    let dir = line.dir();
+    dir.y.atan2(dir.x)
+}
+
+fn same_direction(a: &Line, b: &Line) -> bool {
+    let (da, db) = (a.dir(), b.dir());
+    da.perp(&db).abs() < EPSILON && da.dot(&db) > 0.0
+}
+
+// The largest gap between the directions of two consecutive lines sorted by angle, as the index
+// of the line before the gap and the angle of the gap. The intersection of the lines is bounded if
+// the gap is less than half a turn. Returns `None` if there are no lines.
+fn largest_gap(lines: &[Line]) -> Option<(usize, f64)> {
+    if lines.len() == 1 {
+        return Some((0, std::f64::consts::TAU))
+    }
+
+    (0..lines.len())
+        .map(|i| {
+            let a = angle(&lines[i]);
+            let b = angle(&lines[(i + 1) % lines.len()]);
+            (i, (b - a).rem_euclid(std::f64::consts::TAU))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+// Computes the lines on the boundary of the intersection of a bounded set of lines, sorted by
+// angle. Returns `None` or less than three lines if the intersection is empty.
+fn intersect(lines: &[Line]) -> Option<Vec<Line>> {
+    // A point is only kept if it is strictly inside a line, such that lines through a vertex of
+    // the intersection do not give zero length edges.
+    let outside = |line: &Line, a: &Line, b: &Line| {
+        a.intersection_point(b).is_none_or(|p| line.project_point(p).dist < EPSILON)
+    };
+
+    let mut deque: VecDeque<Line> = VecDeque::new();
+
+    for line in lines {
+        while deque.len() > 1 && outside(line, &deque[deque.len() - 1], &deque[deque.len() - 2]) {
+            deque.pop_back();
+        }
+
+        while deque.len() > 1 && outside(line, &deque[0], &deque[1]) {
+            deque.pop_front();
+        }
+
+        // If the previous line is parallel to this one they face away from each other, and the
+        // lines in between did not bound the intersection.
+        if let Some(back) = deque.back() {
+            back.intersection(line)?;
+        }
+
+        deque.push_back(*line);
+    }
+
+    while deque.len() > 2 && outside(&deque[0], &deque[deque.len() - 1], &deque[deque.len() - 2]) {
+        deque.pop_back();
+    }
+
+    while deque.len() > 2 && outside(&deque[deque.len() - 1], &deque[0], &deque[1]) {
+        deque.pop_front();
+    }
+
+    Some(deque.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{l_shape, line};
+
+    #[test]
+    fn half_planes_square() {
+        let lines = [
+            line(3.0, 0.0, 0.0, 0.0),
+            line(0.0, 3.0, 3.0, 3.0),
+            line(0.0, 0.0, 0.0, 3.0),
+            line(3.0, 3.0, 3.0, 0.0),
+            // Redundant lines, one parallel to an edge and one through a corner.
+            line(4.0, 0.0, 4.0, -1.0),
+            line(2.0, 0.0, 3.0, 1.0).flip(),
+            line(2.0, 0.0, 3.0, 1.0).through_point(na::Point2::new(0.0, 3.0)),
+        ];
+
+        let region = Region2D::from_half_planes(&lines).unwrap();
+        assert_eq!(region.edges().len(), 5);
+        assert!((region.area() - 8.5).abs() < 1e-9);
+        assert!(region.inside(na::Point2::new(1.0, 1.0)));
+        assert!(!region.inside(na::Point2::new(2.9, 0.3)));
+    }
+
+    #[test]
+    fn half_planes_empty_and_unbounded() {
+        let triangle = [
+            line(0.0, 0.0, 0.0, 1.0),
+            line(0.0, 1.0, 1.0, 0.0),
+            line(1.0, 0.0, 0.0, 0.0),
+        ];
+
+        let region = Region2D::from_half_planes(&triangle).unwrap();
+        assert!((region.area() - 0.5).abs() < 1e-9);
+
+        // Flipping one line gives an unbounded region.
+        let unbounded = [triangle[0], triangle[1], triangle[2].flip()];
+        assert!(Region2D::from_half_planes(&unbounded).is_none());
+        assert!(Region2D::from_half_planes(&triangle[..2]).is_none());
+        assert!(Region2D::from_half_planes(&[]).is_none());
+
+        // Flipping all lines gives an empty region.
+        let empty = triangle.map(|l| l.flip());
+        assert!(Region2D::from_half_planes(&empty).unwrap().is_empty());
+
+        // Two parallel lines facing away from each other.
+        let apart = [line(0.0, 0.0, 1.0, 0.0), line(0.0, 1.0, 1.0, 1.0).flip(), triangle[0]];
+        assert!(Region2D::from_half_planes(&apart).unwrap().is_empty());
+
+        // A strip is unbounded.
+        let strip = [line(0.0, 0.0, 1.0, 0.0).flip(), line(0.0, 1.0, 1.0, 1.0), triangle[0]];
+        assert!(Region2D::from_half_planes(&strip).is_none());
+        assert!(Region2D::from_half_planes(&strip[..2]).is_none());
+        assert!(Region2D::from_half_planes(&[apart[0], apart[1]]).unwrap().is_empty());
+
+        // A wedge cut by a parallel line, with a gap of more than half a turn.
+        let wedge = [line(0.0, 0.0, 1.0, 0.0).flip(), line(0.0, 0.0, 1.0, 1.0), line(5.0, 0.0, 6.0, 1.0).flip()];
+        assert!(Region2D::from_half_planes(&wedge).is_none());
+    }
+
+    #[test]
+    fn convex_hull_points() {
+        let mut points = vec![
+            na::Point2::new(0.0, 0.0),
+            na::Point2::new(2.0, 0.0),
+            na::Point2::new(2.0, 2.0),
+            na::Point2::new(0.0, 2.0),
+            na::Point2::new(1.0, 1.0),
+            na::Point2::new(0.5, 1.5),
+            na::Point2::new(2.0, 2.0),
+        ];
+
+        // Points on the edges, exactly and almost collinear.
+        for i in 0..100 {
+            let t = i as f64 / 100.0;
+            points.push(na::Point2::new(0.0, 2.0 * t));
+            points.push(na::Point2::new(2.0 * t, 2.0 * t));
+            points.push(na::Point2::new(0.1 + 1.9 * t, 0.1 * (1.0 - t)));
+        }
+
+        let hull = Region2D::from_convex_hull(&points);
+        assert_eq!(hull.edges().len(), 4);
+        assert!((hull.area() - 4.0).abs() < 1e-9);
+        assert!(hull.inside(na::Point2::new(1.9, 0.1)));
+
+        let line: Vec<_> = (0..10).map(|i| na::Point2::new(i as f64, 2.0 * i as f64)).collect();
+        assert!(Region2D::from_convex_hull(&line).is_empty());
+        assert!(Region2D::from_convex_hull(&[]).is_empty());
+    }
+
+    #[test]
+    fn convex_hull_region() {
+        let hull = l_shape().convex_hull();
+        assert_eq!(hull.edges().len(), 5);
+        assert!((hull.area() - 7.0).abs() < 1e-9);
+        assert!(hull.inside(na::Point2::new(1.5, 1.5)));
+    }
+
+    #[test]
+    fn half_planes_polygon() {
+        let n = 100;
+        let lines: Vec<_> = (0..n)
+            .map(|i| {
+                let a = std::f64::consts::TAU * i as f64 / n as f64;
+                let normal = na::Unit::new_normalize(na::Vector2::new(a.cos(), a.sin()));
+                Line::from_point_normal(na::Point2::from(-normal.into_inner()), normal)
+            })
+            .collect();
+
+        let region = Region2D::from_half_planes(&lines).unwrap();
+        assert_eq!(region.edges().len(), n);
+        assert!((region.area() - n as f64 * (std::f64::consts::PI / n as f64).tan()).abs() < 1e-9);
+    }
+}
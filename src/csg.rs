@@ -0,0 +1,302 @@
+//! Boolean operations on 3d regions.
+//!
+//! Each face of the result is a part of a face of one of the regions, which is found by 2d
+//! booleans of the face with the cross-sections of the other region just outside and just inside
+//! the face plane, see [`Region3D::section`]. A face of the union is kept where the other region is
+//! not on its outside, a face of the intersection where the other region is on its inside, and
+//! the difference is the intersection with the complement of the second region, whose faces are
+//! the flipped faces of the region.
+//!
+//! Faces of both regions in the same plane with the same direction would be kept twice, so the
+//! faces of the second region are only kept where the first region is on both sides of them.
+//! Parts of faces in the same plane are merged into one face afterwards.
+//!
+//! The planes of the result are planes of the two regions. Each edge of a part keeps the plane
+//! it lies on, so parts that are moved to the frame of another plane are rebuilt from the
+//! intersections of the planes, and the vertices are computed from three planes. No new geometry
+//! is introduced.
+//!
+//! Each face takes two sections of the other region, which are linear in its number of faces, so
+//! the cost is quadratic in the number of faces, on top of the 2d booleans of the faces.
+
+use crate::brep_2d::Region2D;
+use crate::brep_3d::{face_from_planes, Region3D};
+use crate::geometry_3d::Plane;
+use crate::relation::BooleanOp;
+
+impl Region3D {
+    /// Computes a boolean operation of two regions.
+    ///
+    /// Returns `None` if the result can not be represented as a region, for example if two parts
+    /// of it only touch along an edge.
+    pub fn boolean(&self, other: &Region3D, op: BooleanOp) -> Option<Region3D> {
+        // The planes of both regions, with the planes of the second region flipped for the
+        // difference.
+        let flip = op == BooleanOp::Difference;
+        let mut planes = self.planes().to_vec();
+        planes.extend(other.planes().iter().map(|p| if flip { p.flip() } else { *p }));
+        let a = Operand { region: self, offset: 0 };
+        let b = Operand { region: other, offset: self.planes().len() };
+
+        let mut parts = match op {
+            BooleanOp::Union => face_parts(&planes, a, b, false, |face, outside, _| {
+                face.boolean(outside, BooleanOp::Difference)
+            })?,
+            BooleanOp::Intersection => face_parts(&planes, a, b, false, |face, _, inside| {
+                face.boolean(inside, BooleanOp::Intersection)
+            })?,
+            BooleanOp::Difference => face_parts(&planes, a, b, false, |face, _, inside| {
+                face.boolean(inside, BooleanOp::Difference)
+            })?,
+        };
+
+        parts.extend(match op {
+            BooleanOp::Union => face_parts(&planes, b, a, false, |face, outside, inside| {
+                face.boolean(outside, BooleanOp::Difference)?.boolean(inside, BooleanOp::Difference)
+            })?,
+            BooleanOp::Intersection => face_parts(&planes, b, a, false, |face, outside, inside| {
+                face.boolean(outside, BooleanOp::Intersection)?.boolean(inside, BooleanOp::Intersection)
+            })?,
+            BooleanOp::Difference => face_parts(&planes, b, a, true, |face, outside, inside| {
+                face.boolean(outside, BooleanOp::Intersection)?.boolean(inside, BooleanOp::Intersection)
+            })?,
+        });
+
+        // Merge the parts in the same plane.
+        let mut merged: Vec<(usize, Part)> = Vec::new();
+        for (p, part) in parts {
+            match merged.iter().position(|(q, _)| planes[*q].coincident(&planes[p])) {
+                Some(i) => {
+                    let part = if merged[i].0 == p { part } else { part.rebuild(&planes, merged[i].0, false)? };
+                    merged[i].1 = merged[i].1.boolean(&part, BooleanOp::Union)?;
+                }
+                None => merged.push((p, part)),
+            }
+        }
+
+        if merged.is_empty() {
+            return Some(Region3D::empty())
+        }
+
+        // The face planes of the result, and the face plane of the result in the same place as
+        // each plane of the regions.
+        let face_planes: Vec<_> = merged.iter().map(|(p, _)| planes[*p]).collect();
+        let face_plane = |p: &Plane| {
+            face_planes.iter().position(|q| q.coincident(p))
+                .or_else(|| face_planes.iter().position(|q| q.coincident(&p.flip())))
+        };
+        let plane_map: Vec<_> = planes.iter().map(face_plane).collect();
+
+        let faces = merged.iter()
+            .enumerate()
+            .map(|(i, (_, part))| {
+                let (region, sources) = part.region.simplify_sourced()?;
+                let mut line_planes = vec![None; region.lines().len()];
+                for (edge, e) in region.edges().iter().zip(sources) {
+                    line_planes[edge.line] = plane_map[part.planes[e]];
+                }
+                Some((i, region, line_planes.into_iter().collect::<Option<_>>()?))
+            })
+            .collect::<Option<_>>()?;
+
+        Region3D::from_faces_with_neighbours(face_planes, faces)
+    }
+}
+
+// A region together with the offset of its planes in the planes of both regions.
+#[derive(Copy, Clone)]
+struct Operand<'a> {
+    region: &'a Region3D,
+    offset: usize,
+}
+
+// A region in the local frame of a plane, with the plane each edge lies on.
+struct Part {
+    region: Region2D,
+    planes: Vec<usize>,
+}
+
+impl Part {
+    fn new((region, planes): (Region2D, Vec<usize>)) -> Self {
+        Self {
+            region,
+            planes,
+        }
+    }
+
+    fn boolean(&self, other: &Part, op: BooleanOp) -> Option<Part> {
+        let (region, sources) = self.region.boolean_sourced(&other.region, op)?;
+        let n = self.planes.len();
+        let planes = sources.into_iter()
+            .map(|e| if e < n { self.planes[e] } else { other.planes[e - n] })
+            .collect();
+
+        Some(Self {
+            region,
+            planes,
+        })
+    }
+
+    // The part in the local frame of the plane `p`, rebuilt from the planes of its edges. If
+    // `reverse` is set the part is seen from the other side of the plane, so its loops are
+    // reversed.
+    fn rebuild(&self, planes: &[Plane], p: usize, reverse: bool) -> Option<Part> {
+        let loops: Vec<Vec<_>> = self.region.loops().into_iter()
+            .map(|l| {
+                let mut l: Vec<_> = l.into_iter().map(|e| self.planes[e]).collect();
+                if reverse {
+                    l.reverse();
+                }
+                l
+            })
+            .collect();
+
+        let (region, line_planes) = face_from_planes(planes, p, &loops)?;
+        let edge_planes = region.edges().iter().map(|e| line_planes[e.line]).collect();
+        Some(Self::new((region, edge_planes)))
+    }
+}
+
+// The parts of the faces of one region to keep, given the face and the cross-sections of the
+// other region just outside and just inside the face plane, all in the frame of the face plane.
+//
+// If `flip` is set the faces are flipped first, and `planes` already holds the flipped planes.
+fn face_parts<F>(planes: &[Plane], region: Operand, other: Operand, flip: bool, keep: F) -> Option<Vec<(usize, Part)>>
+where
+    F: Fn(&Part, &Part, &Part) -> Option<Part>,
+{
+    let mut parts = Vec::new();
+
+    for face in region.region.faces() {
+        let p = region.offset + face.plane;
+        let plane = planes[p];
+        let face = Part::new((
+            face.region.clone(),
+            face.links.iter().map(|l| region.offset + region.region.faces()[l.face].plane).collect(),
+        ));
+        let face = if flip { face.rebuild(planes, p, true)? } else { face };
+
+        let section = |plane: &Plane| {
+            let (section, sources) = other.region.section_sourced(plane)?;
+            Some(Part::new((section, sources.into_iter().map(|q| other.offset + q).collect())))
+        };
+        let outside = section(&plane)?;
+        let inside = section(&plane.flip())?.rebuild(planes, p, true)?;
+
+        let part = keep(&face, &outside, &inside)?;
+        if !part.region.is_empty() {
+            parts.push((p, part));
+        }
+    }
+
+    Some(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::cuboid;
+    use crate::brep_3d::FaceEdge;
+
+    #[test]
+    fn overlapping_cuboids() {
+        let a = cuboid([0.0, 0.0, 0.0], [2.0, 2.0, 2.0]);
+        let b = cuboid([1.0, 1.0, 1.0], [3.0, 3.0, 3.0]);
+
+        let union = a.boolean(&b, BooleanOp::Union).unwrap();
+        assert!((union.volume() - 15.0).abs() < 1e-9);
+        assert!(union.inside(na::Point3::new(2.5, 2.5, 2.5)));
+        assert!(!union.inside(na::Point3::new(2.5, 0.5, 0.5)));
+
+        let intersection = a.boolean(&b, BooleanOp::Intersection).unwrap();
+        assert_eq!(intersection.faces().len(), 6);
+        assert!((intersection.volume() - 1.0).abs() < 1e-9);
+
+        let difference = a.boolean(&b, BooleanOp::Difference).unwrap();
+        assert!((difference.volume() - 7.0).abs() < 1e-9);
+        assert!(!difference.inside(na::Point3::new(1.5, 1.5, 1.5)));
+
+        // The vertices of the result are intersections of planes of the input.
+        let planes: Vec<_> = a.planes().iter().chain(b.planes()).collect();
+        for f in 0..difference.faces().len() {
+            for e in 0..difference.faces()[f].region.edges().len() {
+                let (p, _) = difference.edge_points(FaceEdge { face: f, edge: e });
+                let on_planes = planes.iter().filter(|q| q.distance(p).abs() < 1e-9).count();
+                assert!(on_planes >= 3);
+            }
+        }
+    }
+
+    #[test]
+    fn rotated_cuboids() {
+        let a = cuboid([-1.0, -1.0, 0.0], [1.0, 1.0, 1.0]);
+        let rotation = na::Rotation3::from_axis_angle(&na::Vector3::z_axis(), std::f64::consts::FRAC_PI_4);
+        let vertices: Vec<_> = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)].iter()
+            .flat_map(|(x, y)| [rotation * na::Point3::new(*x, *y, 0.0), rotation * na::Point3::new(*x, *y, 1.0)])
+            .collect();
+        let faces = vec![
+            vec![vec![0, 6, 4, 2]],
+            vec![vec![1, 3, 5, 7]],
+            vec![vec![0, 2, 3, 1]],
+            vec![vec![2, 4, 5, 3]],
+            vec![vec![4, 6, 7, 5]],
+            vec![vec![6, 0, 1, 7]],
+        ];
+        let b = Region3D::from_polyhedron(&vertices, &faces).unwrap();
+
+        let octagon = 8.0 * (2f64.sqrt() - 1.0);
+        let intersection = a.boolean(&b, BooleanOp::Intersection).unwrap();
+        assert_eq!(intersection.faces().len(), 10);
+        assert!((intersection.volume() - octagon).abs() < 1e-9);
+        assert!((a.boolean(&b, BooleanOp::Union).unwrap().volume() - (8.0 - octagon)).abs() < 1e-9);
+        assert!((a.boolean(&b, BooleanOp::Difference).unwrap().volume() - (4.0 - octagon)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn cavity_and_disjoint_cuboids() {
+        let a = cuboid([0.0, 0.0, 0.0], [3.0, 3.0, 3.0]);
+        let b = cuboid([1.0, 1.0, 1.0], [2.0, 2.0, 2.0]);
+
+        let cavity = a.boolean(&b, BooleanOp::Difference).unwrap();
+        assert_eq!(cavity.faces().len(), 12);
+        assert!((cavity.volume() - 26.0).abs() < 1e-9);
+        assert!(!cavity.inside(na::Point3::new(1.5, 1.5, 1.5)));
+        assert!(cavity.inside(na::Point3::new(0.5, 1.5, 1.5)));
+
+        assert!((a.boolean(&b, BooleanOp::Union).unwrap().volume() - 27.0).abs() < 1e-9);
+        assert!(b.boolean(&a, BooleanOp::Difference).unwrap().is_empty());
+
+        let c = cuboid([4.0, 0.0, 0.0], [5.0, 1.0, 1.0]);
+        assert!(a.boolean(&c, BooleanOp::Intersection).unwrap().is_empty());
+        assert!((a.boolean(&c, BooleanOp::Union).unwrap().volume() - 28.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn coplanar_faces() {
+        let a = cuboid([0.0, 0.0, 0.0], [1.0, 1.0, 1.0]);
+
+        // Cuboids sharing a face glue into one cuboid.
+        let b = cuboid([1.0, 0.0, 0.0], [2.0, 1.0, 1.0]);
+        let union = a.boolean(&b, BooleanOp::Union).unwrap();
+        assert_eq!(union.faces().len(), 6);
+        assert!((union.volume() - 2.0).abs() < 1e-9);
+        assert!(a.boolean(&b, BooleanOp::Intersection).unwrap().is_empty());
+        assert!((a.boolean(&b, BooleanOp::Difference).unwrap().volume() - 1.0).abs() < 1e-9);
+
+        // A step, where the side faces of both cuboids are in the same planes.
+        let c = cuboid([0.0, 0.0, 1.0], [1.0, 0.5, 2.0]);
+        let union = a.boolean(&c, BooleanOp::Union).unwrap();
+        assert_eq!(union.faces().len(), 8);
+        assert!((union.volume() - 1.5).abs() < 1e-9);
+
+        // Removing a slot open on one side.
+        let d = cuboid([0.25, -1.0, 0.5], [0.75, 0.5, 2.0]);
+        let slot = a.boolean(&d, BooleanOp::Difference).unwrap();
+        assert!((slot.volume() - 0.875).abs() < 1e-9);
+        assert!((a.boolean(&d, BooleanOp::Intersection).unwrap().volume() - 0.125).abs() < 1e-9);
+
+        assert!((a.boolean(&a, BooleanOp::Union).unwrap().volume() - 1.0).abs() < 1e-9);
+        assert!((a.boolean(&a, BooleanOp::Intersection).unwrap().volume() - 1.0).abs() < 1e-9);
+        assert!(a.boolean(&a, BooleanOp::Difference).unwrap().is_empty());
+    }
+}
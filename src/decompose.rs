@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+
+use crate::brep_2d::Region2D;
+use crate::geometry::Line;
+use crate::predicates::orient2d;
+
+impl Region2D {
+    /// Decomposes the region into convex pieces.
+    ///
+    /// The region is triangulated, after which the triangles are merged for as long as the merged
+    /// pieces stay convex (Hertel-Mehlhorn). The result has at most four times as many pieces as
+    /// the smallest possible decomposition without new vertices.
+    ///
+    /// Edges of the pieces on the boundary of the region use the lines of the region, and edges
+    /// between two pieces use the same line with opposite directions in the two pieces.
+    pub fn convex_decomposition(&self) -> Option<Vec<Region2D>> {
+        let triangulation = self.triangulate();
+        let points = &triangulation.points;
+
+        // Counterclockwise cycles of point indices, merged pieces are stored at their root.
+        let mut pieces: Vec<Vec<usize>> = triangulation.triangles.iter().map(|t| t.to_vec()).collect();
+        let mut parent: Vec<_> = (0..pieces.len()).collect();
+
+        fn find(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+
+        let mut edge_pieces: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (t, tri) in triangulation.triangles.iter().enumerate() {
+            for i in 0..3 {
+                let (a, b) = (tri[i], tri[(i + 1) % 3]);
+                edge_pieces.entry((a.min(b), a.max(b))).or_default().push(t);
+            }
+        }
+
+        // Remove the longest diagonals first, as they are the most likely to give thin pieces.
+        let mut diagonals: Vec<_> = edge_pieces.iter()
+            .filter(|(_, ts)| ts.len() == 2)
+            .map(|(d, ts)| (*d, ts[0], ts[1]))
+            .collect();
+        diagonals.sort_by(|(d1, _, _), (d2, _, _)| {
+            let len = |(a, b): (usize, usize)| (points[a] - points[b]).norm_squared();
+            len(*d2).total_cmp(&len(*d1)).then(d1.cmp(d2))
+        });
+
+        for ((u, v), t1, t2) in diagonals {
+            let (r1, r2) = (find(&mut parent, t1), find(&mut parent, t2));
+            if r1 == r2 {
+                continue
+            }
+
+            if let Some(merged) = merge_convex(points, &pieces[r1], &pieces[r2], u, v) {
+                pieces[r1] = merged;
+                pieces[r2].clear();
+                parent[r2] = r1;
+            }
+        }
+
+        // The triangulation keeps the vertices of the region exactly, so the boundary edges of the
+        // pieces are found by the vertices at the ends of the edges of the region.
+        let key = |p: na::Point2<f64>| [p.x.to_bits(), p.y.to_bits()];
+        let boundary_lines: HashMap<_, _> = (0..self.edges().len())
+            .map(|e| {
+                let a = self.edge_end_vertex(self.edge_prev(e));
+                let b = self.edge_end_vertex(e);
+                ((key(a), key(b)), self.edge_line(e))
+            })
+            .collect();
+
+        // Lines of the diagonals, directed from the lower to the higher point index.
+        let mut diagonal_lines = HashMap::new();
+
+        pieces.into_iter()
+            .filter(|p| !p.is_empty())
+            .map(|piece| {
+                // Regions have their inside on the right, so the pieces are reversed.
+                let n = piece.len();
+                let lines: Vec<_> = (0..n).rev()
+                    .map(|i| {
+                        let (a, b) = (piece[(i + 1) % n], piece[i]);
+                        if edge_pieces[&(a.min(b), a.max(b))].len() == 1 {
+                            boundary_lines.get(&(key(points[a]), key(points[b]))).copied()
+                        } else {
+                            let line = *diagonal_lines.entry((a.min(b), a.max(b)))
+                                .or_insert_with(|| Line::from_two_points(points[a.min(b)], points[a.max(b)]));
+                            Some(if a < b { line } else { line.flip() })
+                        }
+                    })
+                    .collect::<Option<_>>()?;
+
+                Self::from_loops(lines, &[(0..n).collect()])
+            })
+            .collect()
+    }
+}
+
+// Merges two convex counterclockwise pieces sharing the diagonal between `u` and `v`, if the
+// result is strictly convex. A straight angle would give two edges of the piece on the same line.
+fn merge_convex(points: &[na::Point2<f64>], p1: &[usize], p2: &[usize], u: usize, v: usize) -> Option<Vec<usize>> {
+    // Pieces sharing more than one edge would not give a simple polygon.
+    let edges = |p: &[usize]| {
+        (0..p.len())
+            .map(|i| {
+                let (a, b) = (p[i], p[(i + 1) % p.len()]);
+                (a.min(b), a.max(b))
+            })
+            .collect::<Vec<_>>()
+    };
+    let e2 = edges(p2);
+    if edges(p1).iter().filter(|e| e2.contains(e)).count() != 1 {
+        return None
+    }
+
+    // Rotate the first piece to start at the end of the diagonal, and the second piece to start
+    // at the start of the diagonal.
+    let rotate = |p: &[usize], start: usize| {
+        let i = p.iter().position(|x| *x == start).unwrap();
+        let mut p = p.to_vec();
+        p.rotate_left(i);
+        p
+    };
+
+    let (first, second) = if p1[(p1.iter().position(|x| *x == u)? + 1) % p1.len()] == v {
+        (rotate(p1, v), rotate(p2, u))
+    } else {
+        (rotate(p1, u), rotate(p2, v))
+    };
+
+    let mut merged = first;
+    merged.extend(&second[1..second.len() - 1]);
+
+    let n = merged.len();
+    let convex = (0..n).all(|i| {
+        let (a, b, c) = (merged[(i + n - 1) % n], merged[i], merged[(i + 1) % n]);
+        orient2d(points[a], points[b], points[c]) > 0.0
+    });
+
+    if convex {
+        Some(merged)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::polygon;
+
+    fn assert_convex_decomposition(region: &Region2D, pieces: &[Region2D]) {
+        let area: f64 = pieces.iter().map(|p| p.area()).sum();
+        assert!((area - region.area()).abs() < 1e-9);
+
+        for piece in pieces {
+            let vertices = &piece.loop_vertices()[0];
+            let n = vertices.len();
+            assert_eq!(piece.loops().len(), 1);
+            assert!((0..n).all(|i| orient2d(vertices[i], vertices[(i + 1) % n], vertices[(i + 2) % n]) < 0.0));
+
+            // Lines on the boundary are lines of the region.
+            for line in piece.lines() {
+                let on_boundary = region.lines().iter().any(|l| l.coincident(line));
+                let shared = pieces.iter().any(|p| p.lines().iter().any(|l| l.coincident(&line.flip())));
+                assert!(on_boundary || shared);
+            }
+        }
+    }
+
+    #[test]
+    fn decompose_l_shape() {
+        let region = Region2D::from_polygons(&[polygon(&[
+            (0.0, 0.0), (0.0, 3.0), (1.0, 3.0), (1.0, 1.0), (3.0, 1.0), (3.0, 0.0),
+        ])]).unwrap();
+
+        let pieces = region.convex_decomposition().unwrap();
+        assert_eq!(pieces.len(), 2);
+        assert_convex_decomposition(&region, &pieces);
+    }
+
+    #[test]
+    fn merge_keeps_pieces_strictly_convex() {
+        let points = [(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (1.0, 1.0), (1.0, -1.0)].map(|(x, y)| na::Point2::new(x, y));
+
+        // Merging would leave a straight angle at the second point.
+        assert!(merge_convex(&points, &[0, 1, 3], &[1, 2, 3], 1, 3).is_none());
+
+        assert_eq!(merge_convex(&points, &[0, 4, 3], &[4, 2, 3], 4, 3).unwrap(), vec![3, 0, 4, 2]);
+    }
+
+    #[test]
+    fn decompose_region_with_hole() {
+        let region = Region2D::from_polygons(&[
+            polygon(&[(0.0, 0.0), (0.0, 3.0), (3.0, 3.0), (3.0, 0.0)]),
+            polygon(&[(1.0, 1.0), (2.0, 1.0), (2.0, 2.0), (1.0, 2.0)]),
+        ]).unwrap();
+
+        let pieces = region.convex_decomposition().unwrap();
+        assert!(pieces.len() >= 4);
+        assert!(pieces.len() <= 8);
+        assert_convex_decomposition(&region, &pieces);
+
+        let convex = Region2D::from_polygons(&[polygon(&[(0.0, 0.0), (0.0, 3.0), (3.0, 3.0), (3.0, 0.0)])])
+            .unwrap();
+        assert_eq!(convex.convex_decomposition().unwrap().len(), 1);
+        assert!(Region2D::empty().convex_decomposition().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,290 @@
+use crate::brep_2d::Region2D;
+use crate::geometry::LineSegment;
+
+/// The closest point on the boundary of a region to some other point.
+#[derive(Copy, Clone, Debug)]
+pub struct ClosestPoint {
+    /// The closest point on the boundary.
+    pub point: na::Point2<f64>,
+    /// The edge the closest point lies on. If the closest point is a vertex this is one of the
+    /// two edges meeting at the vertex.
+    pub edge: usize,
+    /// The signed distance to the boundary, negative inside the region.
+    pub distance: f64,
+}
+
+impl LineSegment {
+    /// The closest point on the segment to a point.
+    pub fn closest_point(&self, point: na::Point2<f64>) -> na::Point2<f64> {
+        segment_closest(self, point).0
+    }
+}
+
+/// A bounding volume hierarchy over the edges of a region, for closest point queries.
+///
+/// Building the index takes O(n log n) time, after which a query typically takes O(log n) time.
+pub struct EdgeIndex<'a> {
+    region: &'a Region2D,
+    segments: Vec<LineSegment>,
+    // The edges in the order of the leaves.
+    edges: Vec<usize>,
+    nodes: Vec<Node>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Node {
+    min: na::Point2<f64>,
+    max: na::Point2<f64>,
+    kind: NodeKind,
+}
+
+#[derive(Copy, Clone, Debug)]
+enum NodeKind {
+    // A range of `EdgeIndex::edges`.
+    Leaf(usize, usize),
+    Inner(usize, usize),
+}
+
+const LEAF_SIZE: usize = 4;
+
+impl<'a> EdgeIndex<'a> {
+    pub fn new(region: &'a Region2D) -> Self {
+        let segments = (0..region.edges().len())
+            .map(|e| {
+                let (p1, p2) = region.edge_points(e);
+                LineSegment::new(p1, p2)
+            })
+            .collect();
+
+        let mut index = Self {
+            region,
+            segments,
+            edges: (0..region.edges().len()).collect(),
+            nodes: Vec::new(),
+        };
+
+        if !index.edges.is_empty() {
+            index.build(0, index.edges.len());
+        }
+
+        index
+    }
+
+    // Builds the node for a range of edges, splitting at the median along the longest axis of
+    // the bounding box, and returns the index of the node.
+    fn build(&mut self, start: usize, end: usize) -> usize {
+        let (min, max) = self.edges[start..end].iter()
+            .map(|e| &self.segments[*e])
+            .fold((na::Point2::new(f64::INFINITY, f64::INFINITY), na::Point2::new(f64::NEG_INFINITY, f64::NEG_INFINITY)), |(min, max), s| {
+                (min.inf(&s.p1()).inf(&s.p2()), max.sup(&s.p1()).sup(&s.p2()))
+            });
+
+        let node = self.nodes.len();
+        self.nodes.push(Node {
+            min,
+            max,
+            kind: NodeKind::Leaf(start, end),
+        });
+
+        if end - start > LEAF_SIZE {
+            let axis = if max.x - min.x >= max.y - min.y { 0 } else { 1 };
+            let segments = &self.segments;
+            let center = |e: &usize| segments[*e].p1()[axis] + segments[*e].p2()[axis];
+            let mid = (start + end) / 2;
+            self.edges[start..end].select_nth_unstable_by(mid - start, |a, b| center(a).total_cmp(&center(b)));
+
+            let left = self.build(start, mid);
+            let right = self.build(mid, end);
+            self.nodes[node].kind = NodeKind::Inner(left, right);
+        }
+
+        node
+    }
+
+    /// Finds the closest point on the boundary of the region to a point.
+    ///
+    /// The sign of the distance is given by the normals of the closest edge, or the sum of the
+    /// normals of the two edges at the closest vertex. Returns `None` if the region is empty.
+    pub fn closest_point(&self, point: na::Point2<f64>) -> Option<ClosestPoint> {
+        if self.nodes.is_empty() {
+            return None
+        }
+
+        // The closest edge, the closest point and the position on the edge.
+        let mut best: Option<(usize, na::Point2<f64>, f64, f64)> = None;
+        let mut stack = vec![0];
+
+        while let Some(n) = stack.pop() {
+            let node = self.nodes[n];
+            if best.is_some_and(|b| box_distance2(&node, point) >= b.3) {
+                continue
+            }
+
+            match node.kind {
+                NodeKind::Leaf(start, end) => {
+                    for e in &self.edges[start..end] {
+                        let (closest, t) = segment_closest(&self.segments[*e], point);
+                        let dist2 = (closest - point).norm_squared();
+                        if best.is_none_or(|b| dist2 < b.3) {
+                            best = Some((*e, closest, t, dist2));
+                        }
+                    }
+                }
+                NodeKind::Inner(left, right) => {
+                    // Visit the closer child first.
+                    if box_distance2(&self.nodes[left], point) < box_distance2(&self.nodes[right], point) {
+                        stack.extend([right, left]);
+                    } else {
+                        stack.extend([left, right]);
+                    }
+                }
+            }
+        }
+
+        let (edge, closest, t, dist2) = best?;
+
+        let normal = self.region.edge_line(edge).normal().into_inner();
+        let normal = if t <= 0.0 {
+            normal + self.region.edge_line(self.region.edge_prev(edge)).normal().into_inner()
+        } else if t >= 1.0 {
+            normal + self.region.edge_line(self.region.edge_next(edge)).normal().into_inner()
+        } else {
+            normal
+        };
+
+        // The normals point to the inside of the region.
+        let distance = if (point - closest).dot(&normal) > 0.0 {
+            -dist2.sqrt()
+        } else {
+            dist2.sqrt()
+        };
+
+        Some(ClosestPoint {
+            point: closest,
+            edge,
+            distance,
+        })
+    }
+
+    /// The signed distance from a point to the boundary of the region, see
+    /// [`Region2D::signed_distance`].
+    pub fn signed_distance(&self, point: na::Point2<f64>) -> f64 {
+        self.closest_point(point).map_or(f64::INFINITY, |c| c.distance)
+    }
+}
+
+// The squared distance from a point to the bounding box of a node.
+fn box_distance2(node: &Node, point: na::Point2<f64>) -> f64 {
+    let d = (node.min - point).sup(&(point - node.max)).sup(&na::Vector2::zeros());
+    d.norm_squared()
+}
+
+// The closest point on a segment and its position along the segment from zero to one.
+fn segment_closest(segment: &LineSegment, point: na::Point2<f64>) -> (na::Point2<f64>, f64) {
+    let d = segment.p2() - segment.p1();
+    let len2 = d.norm_squared();
+    if len2 == 0.0 {
+        return (segment.p1(), 0.0)
+    }
+
+    let t = ((point - segment.p1()).dot(&d) / len2).clamp(0.0, 1.0);
+    (segment.p1() + d * t, t)
+}
+
+impl Region2D {
+    /// Creates an index over the edges of the region for repeated closest point queries.
+    pub fn edge_index(&self) -> EdgeIndex<'_> {
+        EdgeIndex::new(self)
+    }
+
+    /// Finds the closest point on the boundary of the region to a point.
+    ///
+    /// This builds an [`EdgeIndex`] for the query, so use [`Self::edge_index`] for many queries.
+    /// Returns `None` if the region is empty.
+    pub fn closest_point(&self, point: na::Point2<f64>) -> Option<ClosestPoint> {
+        self.edge_index().closest_point(point)
+    }
+
+    /// The signed distance from a point to the boundary of the region, negative inside the
+    /// region.
+    ///
+    /// The distance to an empty region is infinite.
+    pub fn signed_distance(&self, point: na::Point2<f64>) -> f64 {
+        self.closest_point(point).map_or(f64::INFINITY, |c| c.distance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::l_shape;
+
+    #[test]
+    fn closest_point_on_edge() {
+        let region = l_shape();
+
+        let c = region.closest_point(na::Point2::new(0.3, 2.0)).unwrap();
+        assert!((c.point - na::Point2::new(0.0, 2.0)).norm() < 1e-9);
+        assert!((c.distance + 0.3).abs() < 1e-9);
+        let (p1, p2) = region.edge_points(c.edge);
+        assert_eq!(p1.x, 0.0);
+        assert_eq!(p2.x, 0.0);
+
+        let c = region.closest_point(na::Point2::new(2.0, 3.0)).unwrap();
+        assert!((c.point - na::Point2::new(1.0, 3.0)).norm() < 1e-9);
+        assert!((c.distance - 1.0).abs() < 1e-9);
+
+        // Points in the notch are outside.
+        let c = region.closest_point(na::Point2::new(1.2, 2.0)).unwrap();
+        assert!((c.point - na::Point2::new(1.0, 2.0)).norm() < 1e-9);
+        assert!((c.distance - 0.2).abs() < 1e-9);
+
+        let c = region.closest_point(na::Point2::new(-1.0, -1.0)).unwrap();
+        assert!((c.point - na::Point2::new(0.0, 0.0)).norm() < 1e-9);
+        assert!((c.distance - 2f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn signed_distance_sign() {
+        let region = l_shape();
+
+        assert!((region.signed_distance(na::Point2::new(2.5, 0.5)) + 0.5).abs() < 1e-9);
+        assert!((region.signed_distance(na::Point2::new(4.0, 0.5)) - 1.0).abs() < 1e-9);
+        assert!(region.signed_distance(na::Point2::new(3.0, 0.5)).abs() < 1e-9);
+        assert_eq!(Region2D::empty().signed_distance(na::Point2::origin()), f64::INFINITY);
+    }
+
+    #[test]
+    fn edge_index_matches_linear_search() {
+        // A ring of many edges, where points near the reflex corners of the hole test the sign
+        // at vertices.
+        let n = 100;
+        let circle = |r: f64, dir: f64| -> Vec<_> {
+            (0..n)
+                .map(|i| {
+                    let a = dir * std::f64::consts::TAU * i as f64 / n as f64;
+                    na::Point2::new(r * a.cos(), r * a.sin())
+                })
+                .collect()
+        };
+        let region = Region2D::from_polygons(&[circle(10.0, -1.0), circle(4.0, 1.0)]).unwrap();
+        let index = region.edge_index();
+
+        for i in 0..200 {
+            let a = i as f64 * 0.731;
+            let r = 12.0 * ((i as f64 * 0.377).sin() * 0.5 + 0.5);
+            let p = na::Point2::new(r * a.cos(), r * a.sin());
+
+            let c = index.closest_point(p).unwrap();
+            let linear = (0..region.edges().len())
+                .map(|e| {
+                    let (p1, p2) = region.edge_points(e);
+                    (LineSegment::new(p1, p2).closest_point(p) - p).norm()
+                })
+                .fold(f64::INFINITY, f64::min);
+
+            assert!((c.distance.abs() - linear).abs() < 1e-9);
+            assert_eq!(c.distance < 0.0, region.inside(p));
+        }
+    }
+}
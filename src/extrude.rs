@@ -0,0 +1,217 @@
+//! Extrusion of 2d regions into 3d regions.
+//!
+//! The extruded region is bounded by a cap at each end and a side face for each edge of the 2d
+//! region. The plane of a side face contains the line of its edge and is tilted by the taper
+//! angle, so all faces of the result lie on planes given directly by the lines of the region and
+//! the extrusion parameters.
+
+use crate::brep_2d::Region2D;
+use crate::brep_3d::{face_from_planes, Region3D};
+use crate::geometry_3d::Plane;
+
+/// The parameters of an extrusion.
+#[derive(Copy, Clone, Debug)]
+pub struct Extrusion {
+    /// The distance to extrude along the normal of the plane. Negative distances extrude
+    /// against the normal, and a zero distance is an error.
+    pub distance: f64,
+    /// Extrude the distance to both sides of the plane.
+    pub both_sides: bool,
+    /// The draft angle of the side faces in radians. Positive angles make the region smaller
+    /// away from the plane.
+    pub taper: f64,
+}
+
+impl Extrusion {
+    pub fn new(distance: f64) -> Self {
+        Self {
+            distance,
+            both_sides: false,
+            taper: 0.0,
+        }
+    }
+}
+
+impl Region2D {
+    /// Extrudes the region in the local frame of a plane into a 3d region.
+    ///
+    /// Returns `None` if the distance is zero, or if the taper makes the region collapse before
+    /// the end of the extrusion.
+    pub fn extrude(&self, plane: &Plane, params: &Extrusion) -> Option<Region3D> {
+        if params.distance == 0.0 || self.is_empty() {
+            return None
+        }
+
+        let frame = plane.frame();
+        let (sin, cos) = params.taper.sin_cos();
+        let n = plane.normal().into_inner();
+
+        // The plane of the side faces of a line above or below the plane, tilted towards the
+        // inside of the line by the taper.
+        let side_plane = |line: usize, up: bool| {
+            let line = self.lines()[line];
+            let m = frame * na::Vector3::new(line.normal().x, line.normal().y, 0.0);
+            let normal = if up { m * cos - n * sin } else { m * cos + n * sin };
+            Plane::from_point_normal(plane.to_global(line.origin()), na::Unit::new_normalize(normal))
+        };
+
+        let d = params.distance.abs();
+        let (bottom, top) = match (params.both_sides, params.distance > 0.0) {
+            (true, _) => (-d, d),
+            (false, true) => (0.0, d),
+            (false, false) => (-d, 0.0),
+        };
+        let mut planes = vec![plane.offset(bottom), plane.offset(top).flip()];
+
+        // The lines used by edges, and the index of the side planes of each edge.
+        let mut used = Vec::new();
+        let mut side = vec![None; self.lines().len()];
+        let side: Vec<usize> = self.edges().iter()
+            .map(|edge| {
+                *side[edge.line].get_or_insert_with(|| {
+                    used.push(edge.line);
+                    used.len() - 1
+                })
+            })
+            .collect();
+
+        // The planes of the upper and lower side faces, which are the same without a taper. An
+        // extrusion to one side only has the side faces of that side.
+        let k = used.len();
+        let split = params.both_sides && params.taper != 0.0;
+        planes.extend(used.iter().map(|l| side_plane(*l, top > 0.0)));
+        if split {
+            planes.extend(used.iter().map(|l| side_plane(*l, false)));
+        }
+        let upper = |e: usize| 2 + side[e];
+        let lower = |e: usize| if split { 2 + k + side[e] } else { upper(e) };
+
+        // Each face as its plane and loops of neighbouring planes, counterclockwise seen from the
+        // outside.
+        let loops = self.loops();
+        let mut faces = vec![
+            (0, loops.iter().map(|l| l.iter().map(|e| lower(*e)).collect()).collect()),
+            (1, loops.iter().map(|l| l.iter().rev().map(|e| upper(*e)).collect()).collect()),
+        ];
+        for e in 0..self.edges().len() {
+            let (prev, next) = (self.edge_prev(e), self.edge_next(e));
+            if split {
+                faces.push((upper(e), vec![vec![lower(e), upper(prev), 1, upper(next)]]));
+                faces.push((lower(e), vec![vec![0, lower(prev), upper(e), lower(next)]]));
+            } else {
+                faces.push((upper(e), vec![vec![0, upper(prev), 1, upper(next)]]));
+            }
+        }
+
+        let faces = faces.into_iter()
+            .map(|(p, plane_loops): (usize, Vec<Vec<usize>>)| {
+                let (region, line_planes) = face_from_planes(&planes, p, &plane_loops)?;
+                Some((p, region, line_planes))
+            })
+            .collect::<Option<_>>()?;
+
+        Region3D::from_faces_with_neighbours(planes, faces)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{closed, line, mesh_volume, rect, rect_points};
+
+    #[test]
+    fn extrude_prism() {
+        let region = rect(0.0, 0.0, 2.0, 1.0);
+        let plane = Plane::from_point_normal(na::Point3::new(0.0, 0.0, 1.0), na::Vector3::z_axis());
+
+        let solid = region.extrude(&plane, &Extrusion::new(3.0)).unwrap();
+        assert_eq!(solid.faces().len(), 6);
+        assert!((solid.volume() - 6.0).abs() < 1e-9);
+        assert!(solid.inside(plane.to_global(na::Point2::new(1.0, 0.5)) + na::Vector3::z()));
+        assert!(!solid.inside(plane.to_global(na::Point2::new(1.0, 0.5)) - na::Vector3::z()));
+
+        // The side faces are on the planes of the lines.
+        for (f, face) in solid.faces().iter().enumerate().skip(2) {
+            let p = solid.face_plane(f);
+            assert!(p.normal().dot(&na::Vector3::z()).abs() < 1e-9);
+            assert_eq!(face.region.edges().len(), 4);
+        }
+
+        let both = region.extrude(&plane, &Extrusion { both_sides: true, ..Extrusion::new(3.0) }).unwrap();
+        assert_eq!(both.faces().len(), 6);
+        assert!((both.volume() - 12.0).abs() < 1e-9);
+        assert!(both.inside(plane.to_global(na::Point2::new(1.0, 0.5)) - na::Vector3::z()));
+
+        let mesh = solid.to_mesh();
+        assert_eq!(mesh.vertices.len(), 8);
+        assert_eq!(mesh.faces.len(), 12);
+        assert!(closed(&mesh));
+        assert!((mesh_volume(&mesh) - 6.0).abs() < 1e-4);
+
+        assert!(region.extrude(&plane, &Extrusion::new(0.0)).is_none());
+
+        // A negative distance extrudes against the normal.
+        let below = region.extrude(&plane, &Extrusion::new(-3.0)).unwrap();
+        assert!((below.volume() - 6.0).abs() < 1e-9);
+        assert!(below.inside(plane.to_global(na::Point2::new(1.0, 0.5)) - na::Vector3::z()));
+        assert!(!below.inside(plane.to_global(na::Point2::new(1.0, 0.5)) + na::Vector3::z()));
+
+        // Lines without edges get no side planes.
+        let mut lines = region.lines().to_vec();
+        lines.push(line(0.0, 0.0, 1.0, 1.0));
+        let extra = Region2D::from_loops(lines, &[vec![0, 1, 2, 3]]).unwrap();
+        assert_eq!(extra.lines().len(), 5);
+        let solid = extra.extrude(&plane, &Extrusion::new(3.0)).unwrap();
+        assert_eq!(solid.planes().len(), 6);
+    }
+
+    #[test]
+    fn extrude_tapered() {
+        let region = rect(-1.0, -1.0, 1.0, 1.0);
+        let plane = Plane::from_point_normal(na::Point3::origin(), na::Unit::new_normalize(na::Vector3::new(1.0, 2.0, 3.0)));
+        let taper = 0.25f64.atan();
+
+        // A frustum with a 1.5 by 1.5 top.
+        let frustum = region.extrude(&plane, &Extrusion { taper, ..Extrusion::new(1.0) }).unwrap();
+        assert_eq!(frustum.faces().len(), 6);
+        assert!((frustum.volume() - (4.0 + 2.25 + 3.0) / 3.0).abs() < 1e-9);
+
+        let both = region.extrude(&plane, &Extrusion { taper, both_sides: true, ..Extrusion::new(1.0) }).unwrap();
+        assert_eq!(both.faces().len(), 10);
+        assert!((both.volume() - 2.0 * (4.0 + 2.25 + 3.0) / 3.0).abs() < 1e-9);
+        let mesh = both.to_mesh();
+        assert!(closed(&mesh));
+        assert!((mesh_volume(&mesh) - both.volume()).abs() < 1e-4);
+
+        // A negative taper makes the region larger.
+        let flared = region.extrude(&plane, &Extrusion { taper: -taper, ..Extrusion::new(1.0) }).unwrap();
+        assert!((flared.volume() - (4.0 + 6.25 + 5.0) / 3.0).abs() < 1e-9);
+
+        // Against the normal the region gets smaller away from the plane as well.
+        let below = region.extrude(&plane, &Extrusion { taper, ..Extrusion::new(-1.0) }).unwrap();
+        assert!((below.volume() - frustum.volume()).abs() < 1e-9);
+        assert!(below.inside(plane.to_global(na::Point2::new(0.0, 0.0)) - plane.normal().into_inner() * 0.5));
+        assert!(closed(&below.to_mesh()));
+
+        // The region collapses before the end.
+        assert!(region.extrude(&plane, &Extrusion { taper, ..Extrusion::new(5.0) }).is_none());
+    }
+
+    #[test]
+    fn extrude_with_hole() {
+        let mut hole = rect_points(1.0, 1.0, 3.0, 3.0);
+        hole.reverse();
+        let region = Region2D::from_polygons(&[rect_points(0.0, 0.0, 4.0, 4.0), hole]).unwrap();
+        let plane = Plane::from_point_normal(na::Point3::origin(), -na::Vector3::y_axis());
+
+        let solid = region.extrude(&plane, &Extrusion { taper: 0.1, ..Extrusion::new(1.0) }).unwrap();
+        assert_eq!(solid.faces().len(), 10);
+        assert!(!solid.inside(plane.to_global(na::Point2::new(2.0, 2.0)) - 0.5 * na::Vector3::y()));
+        assert!(solid.inside(plane.to_global(na::Point2::new(0.5, 2.0)) - 0.5 * na::Vector3::y()));
+
+        let mesh = solid.to_mesh();
+        assert_eq!(mesh.vertices.len(), 16);
+        assert!(closed(&mesh));
+        assert!((mesh_volume(&mesh) - solid.volume()).abs() < 1e-4);
+    }
+}
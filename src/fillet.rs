@@ -0,0 +1,138 @@
+use crate::brep_2d::Region2D;
+use crate::geometry::Line;
+use crate::offset::arc_lines;
+
+impl Region2D {
+    /// Cuts off corners of the region by chamfers.
+    ///
+    /// Each corner is given by the two edges meeting at it, and is replaced by a new edge between
+    /// the points at `distance` from the corner along the two edges. Returns `None` if the edges
+    /// of a corner are not neighbours, or if a chamfer is too large for its edges.
+    pub fn chamfer(&self, corners: &[(usize, usize)], distance: f64) -> Option<Region2D> {
+        if distance <= 0.0 {
+            return None
+        }
+
+        self.replace_corners(corners, |e, next| {
+            let v = self.edge_end_vertex(e);
+            let p1 = v - self.edge_line(e).dir().into_inner() * distance;
+            let p2 = v + self.edge_line(next).dir().into_inner() * distance;
+            Some(vec![Line::from_two_points(p1, p2)])
+        })
+    }
+
+    /// Rounds corners of the region by fillets of a radius.
+    ///
+    /// Each corner is given by the two edges meeting at it. The fillets are approximated like
+    /// [`JoinType::Round`] with `tolerance`. Returns `None` if the edges of a corner are not
+    /// neighbours or are parallel, or if a fillet is too large for its edges.
+    ///
+    /// [`JoinType::Round`]: crate::offset::JoinType::Round
+    pub fn fillet(&self, corners: &[(usize, usize)], radius: f64, tolerance: f64) -> Option<Region2D> {
+        if radius <= 0.0 {
+            return None
+        }
+
+        self.replace_corners(corners, |e, next| {
+            let l1 = self.edge_line(e);
+            let l2 = self.edge_line(next);
+            let n1 = l1.normal().into_inner();
+            let n2 = l2.normal().into_inner();
+            let sin = n1.perp(&n2);
+
+            // The center is inside the region at convex corners and outside at reflex corners.
+            let radius = if sin < 0.0 { radius } else { -radius };
+            let center = l1.offset(radius).intersection_point(&l2.offset(radius))?;
+
+            Some(arc_lines(center, radius, n1, sin.atan2(n1.dot(&n2)), tolerance))
+        })
+    }
+
+    // Inserts lines at corners, the lines of each corner are given by `f` called with the edge
+    // before the corner and the edge after it, or `None` if the corner can not be replaced.
+    fn replace_corners<F: Fn(usize, usize) -> Option<Vec<Line>>>(&self, corners: &[(usize, usize)], f: F) -> Option<Region2D> {
+        let mut replace = vec![false; self.edges().len()];
+        for (e1, e2) in corners {
+            if self.edge_next(*e1) == *e2 {
+                replace[*e1] = true;
+            } else if self.edge_next(*e2) == *e1 {
+                replace[*e2] = true;
+            } else {
+                return None
+            }
+        }
+
+        let mut lines = self.lines().to_vec();
+        let mut loops = Vec::new();
+
+        for edge_loop in self.loops() {
+            let mut l = Vec::new();
+
+            for (i, e) in edge_loop.iter().enumerate() {
+                l.push(self.edges()[*e].line);
+
+                if replace[*e] {
+                    for line in f(*e, edge_loop[(i + 1) % edge_loop.len()])? {
+                        l.push(lines.len());
+                        lines.push(line);
+                    }
+                }
+            }
+
+            loops.push(l);
+        }
+
+        Self::from_loops(lines, &loops)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::l_shape;
+
+    // The edges before and after the vertex at a point.
+    fn corner(region: &Region2D, p: na::Point2<f64>) -> (usize, usize) {
+        let e = (0..region.edges().len())
+            .find(|e| (region.edge_end_vertex(*e) - p).norm() < 1e-9)
+            .unwrap();
+        (e, region.edge_next(e))
+    }
+
+    #[test]
+    fn chamfer_corners() {
+        let region = l_shape();
+        let convex = corner(&region, na::Point2::new(0.0, 0.0));
+        let reflex = corner(&region, na::Point2::new(1.0, 1.0));
+
+        let chamfered = region.chamfer(&[convex, (reflex.1, reflex.0)], 0.5).unwrap();
+        assert_eq!(chamfered.edges().len(), 8);
+        assert!((chamfered.area() - 5.0).abs() < 1e-9);
+        assert!(!chamfered.inside(na::Point2::new(0.1, 0.1)));
+        assert!(chamfered.inside(na::Point2::new(1.1, 1.1)));
+
+        assert!(region.chamfer(&[convex], 3.5).is_none());
+        assert!(region.chamfer(&[(convex.0, reflex.0)], 0.5).is_none());
+    }
+
+    #[test]
+    fn fillet_corners() {
+        let region = l_shape();
+        let convex = corner(&region, na::Point2::new(3.0, 0.0));
+        let reflex = corner(&region, na::Point2::new(1.0, 1.0));
+
+        let quarter = 1.0 - std::f64::consts::PI / 4.0;
+
+        let filleted = region.fillet(&[convex, reflex], 0.5, 1e-4).unwrap();
+        assert!(filleted.edges().len() > 8);
+        assert!((filleted.area() - 5.0).abs() < 0.25 * quarter * 0.1);
+        assert!(!filleted.inside(na::Point2::new(2.95, 0.05)));
+        assert!(filleted.inside(na::Point2::new(1.05, 1.05)));
+        assert!(filleted.inside(na::Point2::new(2.5, 0.5)));
+
+        let convex_only = region.fillet(&[convex], 0.5, 1e-4).unwrap();
+        assert!(convex_only.area() < 5.0 - 0.25 * quarter * 0.9);
+
+        assert!(region.fillet(&[reflex], 2.5, 1e-4).is_none());
+    }
+}
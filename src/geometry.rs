@@ -36,6 +36,47 @@ impl Line {
         Self::from_point_normal(p, self.n)
     }
 
+    /// Moves the line a distance along its normal, towards its inside.
+    pub fn offset(&self, dist: f64) -> Self {
+        Self {
+            n: self.n,
+            d: self.d + dist,
+        }
+    }
+
+    /// The same line with the opposite direction, and thus the opposite inside.
+    pub fn flip(&self) -> Self {
+        Self {
+            n: -self.n,
+            d: -self.d,
+        }
+    }
+
+    /// Transforms the line by an affine transformation, such as an [`na::Isometry2`],
+    /// [`na::Similarity2`] or [`na::Affine2`].
+    ///
+    /// The inside of the line is mapped to the inside of the transformed line, so a mirroring
+    /// transformation flips the direction of the line. Fails if the matrix is not affine, that is
+    /// if its last row is not `[0, 0, 1]`, or if its linear part is singular.
+    pub fn transform<T: Into<na::Matrix3<f64>>>(&self, t: T) -> Result<Self, TransformError> {
+        let t = affine(t)?;
+        let a = t.matrix().fixed_view::<2, 2>(0, 0).into_owned();
+        let det = a.determinant();
+
+        let line = Line::from_point_dir(t * self.origin(), a * self.dir().into_inner());
+
+        if det < 0.0 {
+            Ok(line.flip())
+        } else {
+            Ok(line)
+        }
+    }
+
+    /// Checks if two lines are the same line with the same direction.
+    pub fn coincident(&self, other: &Line) -> bool {
+        (self.n.into_inner() - other.n.into_inner()).norm() < EPSILON && (self.d - other.d).abs() < EPSILON
+    }
+
     pub fn origin(&self) -> na::Point2<f64> {
         na::Point2::from(self.normal().into_inner() * self.d)
     }
@@ -92,7 +133,7 @@ impl Line {
         let l1 = Line::from_point_normal(point, self.normal());
         let l2 = Line::from_point_normal(point, self.dir());
         let x1 = l2.intersection(&l1).unwrap();
-        let x2 = l2.intersection(&self).unwrap();
+        let x2 = l2.intersection(self).unwrap();
         let x3 = self.intersection(&l2).unwrap();
 
         PointProjection {
@@ -163,13 +204,31 @@ pub struct LineSegment {
 }
 
 impl LineSegment {
-    // This is not public as 
-    fn line(&self) -> Line {
-        Line::from_two_points(self.p1, self.p2)
+    pub fn new(p1: na::Point2<f64>, p2: na::Point2<f64>) -> Self {
+        Self {
+            p1,
+            p2,
+        }
+    }
+
+    /// First endpoint of the segment, ordered along the line the segment was created from.
+    pub fn p1(&self) -> na::Point2<f64> {
+        self.p1
+    }
+
+    /// Second endpoint of the segment, ordered along the line the segment was created from.
+    pub fn p2(&self) -> na::Point2<f64> {
+        self.p2
+    }
+
+    /// Transforms the segment by an affine transformation, see [`Line::transform`].
+    pub fn transform<T: Into<na::Matrix3<f64>>>(&self, t: T) -> Result<Self, TransformError> {
+        let t = affine(t)?;
+        Ok(Self::new(t * self.p1, t * self.p2))
     }
 
     pub fn intersects_line(&self, line: &Line) -> bool {
-        line.segment_intersection(&self).is_some()
+        line.segment_intersection(self).is_some()
     }
 }
 
@@ -212,10 +271,101 @@ fn total_cmp_vec2(v1: na::Vector2<f64>, v2: na::Vector2<f64>) -> std::cmp::Order
 }
 
 
+/// The reason a transformation of a line, segment or region fails.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TransformError {
+    /// The matrix is not affine, its last row is not `[0, 0, 1]`.
+    NotAffine,
+    /// The linear part of the transformation is singular, so it maps the plane to a line or a
+    /// point.
+    Degenerate,
+    /// The transformed region is not valid, see [`Region2D::transform`].
+    ///
+    /// [`Region2D::transform`]: crate::brep_2d::Region2D::transform
+    Invalid,
+}
+
+impl std::fmt::Display for TransformError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransformError::NotAffine => write!(f, "the transformation is not affine"),
+            TransformError::Degenerate => write!(f, "the transformation is degenerate"),
+            TransformError::Invalid => write!(f, "the transformed region is not valid"),
+        }
+    }
+}
+
+impl std::error::Error for TransformError {}
+
+// Converts a matrix to an affine transformation, which fails if the matrix is not affine or its
+// linear part is singular.
+pub(crate) fn affine<T: Into<na::Matrix3<f64>>>(t: T) -> Result<na::Affine2<f64>, TransformError> {
+    let m = t.into();
+    if m.row(2) != na::RowVector3::new(0.0, 0.0, 1.0) {
+        return Err(TransformError::NotAffine)
+    }
+
+    let a = m.fixed_view::<2, 2>(0, 0);
+    let det = a.determinant();
+    if !det.is_finite() || det.abs() <= EPSILON * a.norm_squared() {
+        return Err(TransformError::Degenerate)
+    }
+
+    Ok(na::Affine2::from_matrix_unchecked(m))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn line_transform() {
+        let line = Line::from_two_points(na::Point2::new(1.0, 0.0), na::Point2::new(1.0, 2.0));
+        let inside = na::Point2::new(2.0, 1.0);
+        assert!(line.inside(inside));
+
+        let iso = na::Isometry2::new(na::Vector2::new(3.0, -1.0), 0.7);
+        let mirror = na::Affine2::from_matrix_unchecked(na::Matrix3::new(
+            -1.0, 0.0, 0.5,
+            0.0, 1.0, 0.0,
+            0.0, 0.0, 1.0,
+        ));
+
+        let moved = line.transform(iso).unwrap();
+        assert!(moved.project_point(iso * na::Point2::new(1.0, 5.0)).dist.abs() < 1e-9);
+        assert!(moved.inside(iso * inside));
+
+        let mirrored = line.transform(mirror).unwrap();
+        assert!(mirrored.project_point(na::Point2::new(-0.5, 5.0)).dist.abs() < 1e-9);
+        assert!(mirrored.inside(mirror * inside));
+        assert!(!mirrored.inside(na::Point2::new(0.0, 0.0)));
+
+        let scaled = line.transform(na::Similarity2::new(na::Vector2::zeros(), 0.0, 2.0)).unwrap();
+        assert!((scaled.project_point(na::Point2::origin()).dist + 2.0).abs() < 1e-9);
+
+        let degenerate = na::Affine2::from_matrix_unchecked(na::Matrix3::new(
+            1.0, 2.0, 0.0,
+            2.0, 4.0, 0.0,
+            0.0, 0.0, 1.0,
+        ));
+        assert_eq!(line.transform(degenerate).unwrap_err(), TransformError::Degenerate);
+
+        let segment = LineSegment::new(na::Point2::new(1.0, 0.0), na::Point2::new(1.0, 2.0));
+        assert_eq!(segment.transform(degenerate).unwrap_err(), TransformError::Degenerate);
+        let segment = segment.transform(mirror).unwrap();
+        assert_eq!(segment.p1(), na::Point2::new(-0.5, 0.0));
+        assert_eq!(segment.p2(), na::Point2::new(-0.5, 2.0));
+
+        // Projective matrices are not affine.
+        let projective = na::Matrix3::new(
+            1.0, 0.0, 0.0,
+            0.0, 1.0, 0.0,
+            0.5, 0.0, 1.0,
+        );
+        assert_eq!(line.transform(projective).unwrap_err(), TransformError::NotAffine);
+        assert_eq!(segment.transform(projective).unwrap_err(), TransformError::NotAffine);
+    }
+
     #[test]
     fn line_intersection_and_segment_intersection_same() {
         let p1 = na::Point2::new(3.0, 7.0);
@@ -239,7 +389,6 @@ mod tests {
     #[test]
     fn line_intersection_point_stable() {
         let mut val: f64 = -1.316314;
-        let mut misses = 0;
 
         for _ in 0..1000 {
             val = f64::from_bits(val.to_bits()+1);
@@ -0,0 +1,250 @@
+use crate::geometry::{Line, LinePoint, EPSILON};
+
+/// An oriented plane, the 3d analogue of a [`Line`].
+///
+/// The inside of the plane is the side the normal points to. Each plane has a local frame, where
+/// the x and y axes lie in the plane and the z axis is the normal, which is used to describe 2d
+/// geometry in the plane.
+#[derive(Copy, Clone, Debug)]
+pub struct Plane {
+    n: na::UnitVector3<f64>,
+    d: f64,
+}
+
+impl Plane {
+    pub fn from_point_normal(p0: na::Point3<f64>, n: na::UnitVector3<f64>) -> Self {
+        let d = n.into_inner().dot(&p0.coords);
+        Self {
+            n,
+            d
+        }
+    }
+
+    pub fn from_point_two_vectors(p0: na::Point3<f64>, u: na::Vector3<f64>, w: na::Vector3<f64>) -> Option<Self> {
+        let normal = na::Unit::try_new(u.cross(&w), EPSILON)?;
+        Some(Self::from_point_normal(p0, normal))
+    }
+
+    pub fn from_three_points(p0: na::Point3<f64>, p1: na::Point3<f64>, p2: na::Point3<f64>) -> Option<Self> {
+        Self::from_point_two_vectors(p0, p1 - p0, p2 - p0)
+    }
+
+    /// Moves the plane a distance along its normal, towards its inside.
+    pub fn offset(&self, dist: f64) -> Self {
+        Self {
+            n: self.n,
+            d: self.d + dist,
+        }
+    }
+
+    /// The same plane with the opposite normal, and thus the opposite inside.
+    pub fn flip(&self) -> Self {
+        Self {
+            n: -self.n,
+            d: -self.d,
+        }
+    }
+
+    /// Checks if two planes are the same plane with the same direction.
+    pub fn coincident(&self, other: &Plane) -> bool {
+        (self.n.into_inner() - other.n.into_inner()).norm() < EPSILON && (self.d - other.d).abs() < EPSILON
+    }
+
+    pub fn normal(&self) -> na::UnitVector3<f64> {
+        self.n
+    }
+
+    pub fn origin(&self) -> na::Point3<f64> {
+        na::Point3::from(self.n.into_inner() * self.d)
+    }
+
+    /// The signed distance from the plane to a point, positive on the inside.
+    pub fn distance(&self, point: na::Point3<f64>) -> f64 {
+        self.n.dot(&point.coords) - self.d
+    }
+
+    pub fn inside(&self, point: na::Point3<f64>) -> bool {
+        self.distance(point) >= 0.0
+    }
+
+    /// The intersection of a line with the plane, as a position along the line.
+    ///
+    /// The direction is positive if the line goes into the inside of the plane. Returns `None` if
+    /// the line is parallel to the plane.
+    pub fn intersection(&self, line: &Line3D) -> Option<LinePoint> {
+        let det = self.n.dot(&line.dir());
+        if det.abs() < EPSILON {
+            None
+        } else {
+            Some(LinePoint {
+                pos: -self.distance(line.origin()) / det,
+                dir: det > 0.0,
+            })
+        }
+    }
+
+    /// The local frame of the plane, mapping local coordinates to global coordinates.
+    ///
+    /// The x axis is perpendicular to the coordinate axis least aligned with the normal, so the
+    /// frame only depends on the normal and the origin of the plane.
+    pub fn frame(&self) -> na::Isometry3<f64> {
+        let n = self.n.into_inner();
+        let axis = if n.x.abs() <= n.y.abs() && n.x.abs() <= n.z.abs() {
+            na::Vector3::x()
+        } else if n.y.abs() <= n.z.abs() {
+            na::Vector3::y()
+        } else {
+            na::Vector3::z()
+        };
+
+        let u = n.cross(&axis).normalize();
+        let v = n.cross(&u);
+        let rotation = na::Rotation3::from_basis_unchecked(&[u, v, n]);
+
+        na::Isometry3::from_parts(na::Translation3::from(self.origin().coords), rotation.into())
+    }
+
+    /// Projects a point to the local coordinates of the plane.
+    pub fn to_local(&self, point: na::Point3<f64>) -> na::Point2<f64> {
+        self.frame().inverse_transform_point(&point).xy()
+    }
+
+    /// The point in the plane with the given local coordinates.
+    pub fn to_global(&self, point: na::Point2<f64>) -> na::Point3<f64> {
+        self.frame() * na::Point3::new(point.x, point.y, 0.0)
+    }
+
+    /// The intersection of this plane with another plane, as a line in the local frame of this
+    /// plane.
+    ///
+    /// The inside of the line is the inside of the other plane. Returns `None` if the planes are
+    /// parallel.
+    pub fn intersection_line(&self, other: &Plane) -> Option<Line> {
+        let frame = self.frame();
+        let m = na::Vector2::new(
+            other.n.dot(&(frame * na::Vector3::x())),
+            other.n.dot(&(frame * na::Vector3::y())),
+        );
+
+        let norm = m.norm();
+        if norm < EPSILON {
+            return None
+        }
+
+        let offset = other.d - other.n.dot(&self.origin().coords);
+        let point = na::Point2::from(m * offset / (norm * norm));
+
+        Some(Line::from_point_normal(point, na::Unit::new_unchecked(m / norm)))
+    }
+
+    /// Computes the intersection point of three planes.
+    ///
+    /// Like [`Line::intersection_point`] the result does not depend on the order of the planes,
+    /// so a vertex shared by several faces gets the exact same position from each face.
+    pub fn intersection_point(planes: [&Plane; 3]) -> Option<na::Point3<f64>> {
+        let mut planes = planes;
+        planes.sort_by(|a, b| total_cmp_plane(a, b));
+
+        let m = na::Matrix3::from_rows(&[
+            planes[0].n.transpose(),
+            planes[1].n.transpose(),
+            planes[2].n.transpose(),
+        ]);
+
+        if m.determinant().abs() < EPSILON {
+            return None
+        }
+
+        let b = na::Vector3::new(planes[0].d, planes[1].d, planes[2].d);
+        Some(na::Point3::from(m.lu().solve(&b)?))
+    }
+}
+
+/// A line in 3d space with a direction.
+#[derive(Copy, Clone, Debug)]
+pub struct Line3D {
+    p: na::Point3<f64>,
+    d: na::UnitVector3<f64>,
+}
+
+impl Line3D {
+    pub fn from_point_dir(p: na::Point3<f64>, d: na::Vector3<f64>) -> Self {
+        Self {
+            p,
+            d: na::Unit::new_normalize(d),
+        }
+    }
+
+    pub fn from_two_points(p0: na::Point3<f64>, p1: na::Point3<f64>) -> Self {
+        Self::from_point_dir(p0, p1 - p0)
+    }
+
+    /// The point at position zero along the line.
+    pub fn origin(&self) -> na::Point3<f64> {
+        self.p
+    }
+
+    pub fn dir(&self) -> na::UnitVector3<f64> {
+        self.d
+    }
+
+    pub fn point(&self, pos: f64) -> na::Point3<f64> {
+        self.p + self.d.into_inner() * pos
+    }
+
+    /// The position along the line of the point on the line closest to a point.
+    pub fn project_point(&self, point: na::Point3<f64>) -> f64 {
+        self.d.dot(&(point - self.p))
+    }
+}
+
+// A determenistic ordering of planes, see `total_cmp_vec2`.
+fn total_cmp_plane(a: &Plane, b: &Plane) -> std::cmp::Ordering {
+    a.n.x.total_cmp(&b.n.x)
+        .then(a.n.y.total_cmp(&b.n.y))
+        .then(a.n.z.total_cmp(&b.n.z))
+        .then(a.d.total_cmp(&b.d))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plane_frame_and_intersections() {
+        let p = Plane::from_three_points(
+            na::Point3::new(1.0, 0.0, 0.0),
+            na::Point3::new(0.0, 1.0, 0.0),
+            na::Point3::new(0.0, 0.0, 1.0),
+        ).unwrap();
+        assert!(p.inside(na::Point3::new(1.0, 1.0, 1.0)));
+        assert!((p.distance(na::Point3::origin()) + 1.0 / 3f64.sqrt()).abs() < 1e-9);
+
+        let x = na::Point3::new(0.2, 0.3, 0.5);
+        assert!((p.to_global(p.to_local(x)) - x).norm() < 1e-9);
+
+        let q = Plane::from_point_normal(na::Point3::new(0.5, 0.0, 0.0), na::Vector3::x_axis());
+        let line = p.intersection_line(&q).unwrap();
+        for t in [-1.0, 0.0, 2.0] {
+            let point = p.to_global(line.point(t));
+            assert!(p.distance(point).abs() < 1e-9);
+            assert!(q.distance(point).abs() < 1e-9);
+        }
+        assert!(q.inside(p.to_global(line.point(0.0) + line.normal().into_inner())));
+        assert!(p.intersection_line(&p.offset(1.0)).is_none());
+
+        let r = Plane::from_point_normal(na::Point3::new(0.0, 0.25, 0.0), na::Vector3::y_axis());
+        let a = Plane::intersection_point([&p, &q, &r]).unwrap();
+        let b = Plane::intersection_point([&r, &p, &q]).unwrap();
+        assert_eq!(a, b);
+        assert!((a - na::Point3::new(0.5, 0.25, 0.25)).norm() < 1e-9);
+        assert!(Plane::intersection_point([&p, &q, &q.offset(1.0)]).is_none());
+
+        let line = Line3D::from_two_points(na::Point3::new(2.0, 1.0, 0.0), na::Point3::new(0.0, 1.0, 0.0));
+        let x = q.intersection(&line).unwrap();
+        assert!((x.pos - 1.5).abs() < 1e-9);
+        assert!(!x.dir);
+        assert!((line.project_point(line.point(x.pos)) - x.pos).abs() < 1e-9);
+        assert!(r.intersection(&line).is_none());
+    }
+}
@@ -0,0 +1,240 @@
+//! Regions with attributes on their loops.
+//!
+//! The attributes of a region, such as a material, a layer or the feature the region was created
+//! from, are given per loop. Each edge refers to the label of the loop it came from, so after an
+//! operation the edges of one loop can come from several labelled loops. Operations on a labelled
+//! region compute the result on the plain region together with the source edge of each resulting
+//! edge, which is the input edge it lies on or was offset from.
+
+use crate::brep_2d::Region2D;
+use crate::geometry::Line;
+use crate::offset::JoinType;
+use crate::relation::BooleanOp;
+
+/// A region with labelled loops, where each edge refers to the label of the loop it came from.
+#[derive(Clone, Debug)]
+pub struct LabelledRegion2D<A> {
+    region: Region2D,
+    labels: Vec<A>,
+    // The index in `labels` of each edge.
+    edge_labels: Vec<usize>,
+}
+
+impl<A: Clone> LabelledRegion2D<A> {
+    /// Creates a labelled region from a label for each loop of the region, in the order of
+    /// [`Region2D::loops`].
+    ///
+    /// Returns `None` if the number of labels does not match the number of loops.
+    pub fn new(region: Region2D, labels: Vec<A>) -> Option<Self> {
+        let loops = region.loops();
+        if labels.len() != loops.len() {
+            return None
+        }
+
+        let mut edge_labels = vec![0; region.edges().len()];
+        for (i, l) in loops.iter().enumerate() {
+            for e in l {
+                edge_labels[*e] = i;
+            }
+        }
+
+        Some(Self {
+            region,
+            labels,
+            edge_labels,
+        })
+    }
+
+    /// Creates a labelled region where all loops have the same label.
+    pub fn with_label(region: Region2D, label: A) -> Self {
+        let edge_labels = vec![0; region.edges().len()];
+        Self {
+            region,
+            labels: vec![label],
+            edge_labels,
+        }
+    }
+
+    pub fn region(&self) -> &Region2D {
+        &self.region
+    }
+
+    /// The labels of the loops the edges came from, which are the labels of the input loops and
+    /// not necessarily used by any edge.
+    pub fn labels(&self) -> &[A] {
+        &self.labels
+    }
+
+    /// The index in [`Self::labels`] of the label of an edge.
+    pub fn edge_label_index(&self, e: usize) -> usize {
+        self.edge_labels[e]
+    }
+
+    pub fn edge_label(&self, e: usize) -> &A {
+        &self.labels[self.edge_labels[e]]
+    }
+
+    /// Computes a boolean operation of two labelled regions, see [`Region2D::boolean`].
+    ///
+    /// The labels of the result are the labels of this region followed by the labels of the
+    /// other region.
+    pub fn boolean(&self, other: &Self, op: BooleanOp) -> Option<Self> {
+        let (region, sources) = self.region.boolean_sourced(&other.region, op)?;
+        let n = self.edge_labels.len();
+        let edge_labels = sources.into_iter()
+            .map(|e| if e < n { self.edge_labels[e] } else { self.labels.len() + other.edge_labels[e - n] })
+            .collect();
+
+        let mut labels = self.labels.clone();
+        labels.extend_from_slice(&other.labels);
+        Some(Self {
+            region,
+            labels,
+            edge_labels,
+        })
+    }
+
+    /// Splits the region by a line, see [`Region2D::split`].
+    ///
+    /// The new edges along the line get the given label, which comes after the labels of this
+    /// region.
+    pub fn split(&self, line: Line, label: A) -> Option<(Self, Self)> {
+        let mut labels = self.labels.clone();
+        labels.push(label);
+
+        let part = |line: Line| {
+            let (region, sources) = self.region.clip_sourced(line)?;
+            let edge_labels = sources.into_iter()
+                .map(|e| e.map_or(self.labels.len(), |e| self.edge_labels[e]))
+                .collect();
+
+            Some(Self {
+                region,
+                labels: labels.clone(),
+                edge_labels,
+            })
+        };
+
+        Some((part(line)?, part(line.flip())?))
+    }
+
+    /// Offsets the region by a distance, see [`Region2D::offset`].
+    ///
+    /// The edges of a join between two edges get the label of the first edge.
+    pub fn offset(&self, distance: f64, join: JoinType) -> Option<Self> {
+        let (region, sources) = self.region.offset_sourced(distance, join)?;
+        Some(self.with_sources(region, sources))
+    }
+
+    /// Simplifies the region, see [`Region2D::simplify`].
+    ///
+    /// Edges with different labels on the same line are merged into one edge, which keeps the
+    /// label of one of them.
+    pub fn simplify(&self) -> Option<Self> {
+        let (region, sources) = self.region.simplify_sourced()?;
+        Some(self.with_sources(region, sources))
+    }
+
+    // A region with the same labels, where each edge has the label of its source edge in this
+    // region.
+    fn with_sources(&self, region: Region2D, sources: Vec<usize>) -> Self {
+        Self {
+            region,
+            labels: self.labels.clone(),
+            edge_labels: sources.into_iter().map(|e| self.edge_labels[e]).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::rect;
+
+    fn count(region: &LabelledRegion2D<&str>, label: &str) -> usize {
+        (0..region.region().edges().len()).filter(|e| *region.edge_label(*e) == label).count()
+    }
+
+    #[test]
+    fn labels_through_booleans() {
+        let plate = LabelledRegion2D::with_label(rect(0.0, 0.0, 4.0, 4.0), "plate");
+        let pocket = LabelledRegion2D::with_label(rect(1.0, 1.0, 2.0, 2.0), "pocket");
+        let slot = LabelledRegion2D::with_label(rect(3.0, 1.0, 5.0, 2.0), "slot");
+
+        let r = plate.boolean(&pocket, BooleanOp::Difference).unwrap();
+        assert_eq!(count(&r, "plate"), 4);
+        assert_eq!(count(&r, "pocket"), 4);
+
+        let r = r.boolean(&slot, BooleanOp::Difference).unwrap();
+        assert_eq!(r.region().edges().len(), 12);
+        assert_eq!(count(&r, "plate"), 5);
+        assert_eq!(count(&r, "pocket"), 4);
+        assert_eq!(count(&r, "slot"), 3);
+        assert_eq!(r.labels(), ["plate", "pocket", "slot"]);
+
+        for e in 0..r.region().edges().len() {
+            let (p1, p2) = r.region().edge_points(e);
+            let mid = na::center(&p1, &p2);
+            let expected = if mid.x > 2.9 && mid.y > 0.9 && mid.y < 2.1 {
+                "slot"
+            } else if mid.x > 0.9 && mid.x < 2.1 && mid.y > 0.9 && mid.y < 2.1 {
+                "pocket"
+            } else {
+                "plate"
+            };
+            assert_eq!(*r.edge_label(e), expected);
+        }
+    }
+
+    #[test]
+    fn labels_through_split_offset_and_simplify() {
+        let ring = Region2D::from_polygons(&[
+            vec![
+                na::Point2::new(0.0, 0.0),
+                na::Point2::new(0.0, 4.0),
+                na::Point2::new(4.0, 4.0),
+                na::Point2::new(4.0, 0.0),
+            ],
+            vec![
+                na::Point2::new(3.0, 1.0),
+                na::Point2::new(3.0, 3.0),
+                na::Point2::new(1.0, 3.0),
+                na::Point2::new(1.0, 1.0),
+            ],
+        ]).unwrap();
+        let loop_labels = ring.loops().iter()
+            .map(|l| if l.contains(&0) { "outer" } else { "inner" })
+            .collect();
+        let ring = LabelledRegion2D::new(ring, loop_labels).unwrap();
+        assert!(LabelledRegion2D::new(rect(0.0, 0.0, 1.0, 1.0), vec!["a", "b"]).is_none());
+
+        let line = Line::from_two_points(na::Point2::new(2.0, 0.0), na::Point2::new(2.0, 4.0));
+        let (a, b) = ring.split(line, "cut").unwrap();
+        for r in [&a, &b] {
+            assert_eq!(count(r, "outer"), 3);
+            assert_eq!(count(r, "inner"), 3);
+            assert_eq!(count(r, "cut"), 2);
+        }
+
+        let grown = ring.offset(0.5, JoinType::Square).unwrap();
+        assert_eq!(count(&grown, "outer"), 8);
+        assert_eq!(count(&grown, "inner"), 4);
+
+        let shrunk = ring.offset(-0.25, JoinType::Miter { limit: 2.0 }).unwrap();
+        assert_eq!(count(&shrunk, "outer"), 4);
+        assert_eq!(count(&shrunk, "inner"), 4);
+
+        // Offset edges keep the label of the edge they were offset from.
+        let thin = ring.offset(-0.4, JoinType::Square).unwrap();
+        for e in 0..thin.region().edges().len() {
+            let (p1, p2) = thin.region().edge_points(e);
+            let mid = na::center(&p1, &p2) - na::Point2::new(2.0, 2.0);
+            let outer = mid.x.abs().max(mid.y.abs()) > 1.5;
+            assert_eq!(*thin.edge_label(e), if outer { "outer" } else { "inner" });
+        }
+
+        let simplified = a.simplify().unwrap();
+        assert_eq!(count(&simplified, "cut"), 2);
+        assert_eq!(count(&simplified, "outer"), 3);
+    }
+}
@@ -1,6 +1,29 @@
 extern crate nalgebra as na;
 
 pub mod mesh;
+pub mod brep;
 pub mod geometry;
+pub mod geometry_3d;
 pub mod brep_1d;
 pub mod brep_2d;
+pub mod brep_3d;
+pub mod predicates;
+pub mod triangulation;
+pub mod offset;
+pub mod convex;
+pub mod distance;
+pub mod arrangement;
+pub mod fillet;
+pub mod minkowski;
+pub mod decompose;
+pub mod medial_axis;
+pub mod skeleton;
+pub mod winding;
+pub mod relation;
+pub mod labels;
+pub mod bsp;
+pub mod csg;
+pub mod extrude;
+
+#[cfg(test)]
+mod test_util;
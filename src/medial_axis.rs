@@ -0,0 +1,663 @@
+//! The medial axis of regions.
+//!
+//! The medial axis is the part of the Voronoi diagram of the boundary of a region that lies inside
+//! the region. The sites of the diagram are the edges of the region and its reflex vertices. The
+//! bisector of two edges is a line, the bisector of two vertices is a line, and the bisector of a
+//! vertex and an edge is a parabola.
+//!
+//! Each site is only the closest site in its own domain: an edge in the strip of points whose
+//! closest point on its line is on the edge, and a reflex vertex in the wedge between the normals
+//! of its two edges. The medial axis touches the boundary at the convex vertices, and is traced
+//! from there along the bisectors of the two sites closest to it. A traced edge of the medial axis
+//! ends where a third site becomes as close as the two sites, or where one of the sites leaves its
+//! domain, and new edges are traced from the node there. Each edge is found by testing every site,
+//! so this takes `O(n^2)` time for `n` edges.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::brep_2d::Region2D;
+use crate::geometry::{Line, EPSILON};
+
+/// The medial axis of a region as a graph of line and parabola segments.
+#[derive(Clone, Debug)]
+pub struct MedialAxis {
+    pub nodes: Vec<MedialNode>,
+    pub edges: Vec<MedialEdge>,
+}
+
+/// A node of the medial axis.
+#[derive(Copy, Clone, Debug)]
+pub struct MedialNode {
+    pub point: na::Point2<f64>,
+    /// The distance from the node to the boundary of the region, zero at convex vertices of the
+    /// region.
+    pub radius: f64,
+}
+
+/// An edge of the medial axis between two nodes.
+#[derive(Copy, Clone, Debug)]
+pub struct MedialEdge {
+    pub nodes: (usize, usize),
+    pub curve: MedialCurve,
+}
+
+/// The curve of an edge of the medial axis.
+#[derive(Copy, Clone, Debug)]
+pub enum MedialCurve {
+    /// A straight line between the nodes.
+    Line,
+    /// A piece of the parabola of points equally far from `focus` and `directrix`.
+    Parabola {
+        focus: na::Point2<f64>,
+        directrix: Line,
+    },
+}
+
+impl MedialAxis {
+    /// A point on an edge, going from the first node at `t = 0` to the second node at `t = 1`.
+    pub fn edge_point(&self, e: usize, t: f64) -> na::Point2<f64> {
+        let edge = &self.edges[e];
+        let p1 = self.nodes[edge.nodes.0].point;
+        let p2 = self.nodes[edge.nodes.1].point;
+
+        match edge.curve {
+            MedialCurve::Line => p1 + (p2 - p1) * t,
+            MedialCurve::Parabola { focus, directrix } => {
+                let bisector = Bisector::Parabola { focus, directrix };
+                let (u1, u2) = (bisector.param(p1), bisector.param(p2));
+                bisector.point(u1 + (u2 - u1) * t)
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum Site {
+    Edge(usize),
+    // A reflex vertex, given by the edge ending at it.
+    Vertex(usize),
+}
+
+// The curve of points equally far from two sites.
+#[derive(Copy, Clone, Debug)]
+enum Bisector {
+    Line {
+        origin: na::Point2<f64>,
+        dir: na::Vector2<f64>,
+    },
+    Parabola {
+        focus: na::Point2<f64>,
+        directrix: Line,
+    },
+}
+
+impl Bisector {
+    // The line of points `x` with `m.dot(x) = c`.
+    fn line(m: na::Vector2<f64>, c: f64) -> Option<Self> {
+        let len2 = m.norm_squared();
+        if len2 == 0.0 {
+            return None
+        }
+
+        Some(Bisector::Line {
+            origin: na::Point2::from(m * c / len2),
+            dir: na::Vector2::new(-m.y, m.x) / len2.sqrt(),
+        })
+    }
+
+    fn param(&self, p: na::Point2<f64>) -> f64 {
+        match self {
+            Bisector::Line { origin, dir } => (p - origin).dot(dir),
+            Bisector::Parabola { focus: _, directrix } => directrix.project_point(p).pos,
+        }
+    }
+
+    fn point(&self, t: f64) -> na::Point2<f64> {
+        match self {
+            Bisector::Line { origin, dir } => origin + dir * t,
+            Bisector::Parabola { focus, directrix } => {
+                let projection = directrix.project_point(*focus);
+                let h = projection.dist;
+                let u = t - projection.pos;
+                directrix.point(t) + directrix.normal().into_inner() * (u * u + h * h) / (2.0 * h)
+            }
+        }
+    }
+
+    // The direction of the bisector at a parameter, towards increasing parameters.
+    fn tangent(&self, t: f64) -> na::Vector2<f64> {
+        match self {
+            Bisector::Line { origin: _, dir } => *dir,
+            Bisector::Parabola { focus, directrix } => {
+                let projection = directrix.project_point(*focus);
+                let u = t - projection.pos;
+                directrix.dir().into_inner() + directrix.normal().into_inner() * u / projection.dist
+            }
+        }
+    }
+
+    // The parameters of the points `x` on the bisector with `m.dot(x) = c`.
+    fn solve(&self, m: na::Vector2<f64>, c: f64) -> Vec<f64> {
+        match self {
+            Bisector::Line { origin, dir } => {
+                let k = m.dot(dir);
+                if k == 0.0 {
+                    Vec::new()
+                } else {
+                    vec![(c - m.dot(&origin.coords)) / k]
+                }
+            }
+            Bisector::Parabola { focus, directrix } => {
+                // A quadratic equation in the parameter relative to the vertex of the parabola.
+                let projection = directrix.project_point(*focus);
+                let h = projection.dist;
+                let n = directrix.normal().into_inner();
+                let qa = m.dot(&n) / (2.0 * h);
+                let qb = m.dot(&directrix.dir());
+                let qc = m.dot(&directrix.point(projection.pos).coords) + m.dot(&n) * h / 2.0 - c;
+
+                quadratic_roots(qa, qb, qc).into_iter().map(|u| projection.pos + u).collect()
+            }
+        }
+    }
+}
+
+// The real roots of `a * x^2 + b * x + c`.
+fn quadratic_roots(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if a.abs() < EPSILON * EPSILON {
+        if b.abs() < EPSILON * EPSILON {
+            Vec::new()
+        } else {
+            vec![-c / b]
+        }
+    } else {
+        let disc = b * b - 4.0 * a * c;
+        if disc < 0.0 {
+            Vec::new()
+        } else {
+            let sqrt = disc.sqrt();
+            vec![(-b - sqrt) / (2.0 * a), (-b + sqrt) / (2.0 * a)]
+        }
+    }
+}
+
+// The state of tracing the medial axis of a region.
+struct Tracer<'a> {
+    region: &'a Region2D,
+    sites: Vec<Site>,
+    axis: MedialAxis,
+    // The nodes by the sites closest to them. Different nodes can have the same closest sites,
+    // as a parabola can cross another bisector twice.
+    node_ids: HashMap<Vec<usize>, Vec<usize>>,
+    // The bisectors traced or to trace from a node, given by the node, the two sites and whether
+    // the bisector is followed forward.
+    traced: HashSet<(usize, usize, usize, bool)>,
+    stack: Vec<(usize, usize, usize, bool)>,
+}
+
+impl Tracer<'_> {
+    fn trace(&mut self) {
+        for e in 0..self.region.edges().len() {
+            if !self.region.reflex_vertex(e) {
+                self.node(self.region.edge_end_vertex(e), 0.0);
+            }
+        }
+
+        while let Some((n, a, b, forward)) = self.stack.pop() {
+            if !self.traced.insert((n, a, b, forward)) {
+                continue
+            }
+
+            let Some((bisector, curve)) = self.region.site_bisector(self.sites[a], self.sites[b]) else {
+                continue
+            };
+
+            let start = self.axis.nodes[n].point;
+            let Some(end) = self.next_node(a, b, &bisector, bisector.param(start), forward) else {
+                continue
+            };
+
+            let m = self.node(end, self.region.site_line_distance(self.sites[a], end));
+            self.traced.insert((m, a, b, !forward));
+            if m != n {
+                self.axis.edges.push(MedialEdge {
+                    nodes: (n, m),
+                    curve,
+                });
+            }
+        }
+    }
+
+    // Finds or adds the node at a point, and adds the bisectors leaving a new node to the stack.
+    fn node(&mut self, point: na::Point2<f64>, radius: f64) -> usize {
+        let sites: Vec<_> = (0..self.sites.len())
+            .filter(|s| self.region.site_distance(self.sites[*s], point).is_some_and(|d| (d - radius).abs() < EPSILON))
+            .collect();
+
+        let same = self.node_ids.get(&sites).into_iter().flatten();
+        if let Some(n) = same.copied().find(|n| (self.axis.nodes[*n].point - point).norm() < EPSILON) {
+            return n
+        }
+
+        let n = self.axis.nodes.len();
+        self.axis.nodes.push(MedialNode {
+            point,
+            radius,
+        });
+
+        for (i, a) in sites.iter().enumerate() {
+            for b in &sites[i + 1..] {
+                for forward in [false, true] {
+                    if self.leaves(point, &sites, *a, *b, forward) {
+                        self.stack.push((n, *a, *b, forward));
+                    }
+                }
+            }
+        }
+
+        self.node_ids.entry(sites).or_default().push(n);
+        n
+    }
+
+    // Checks if the bisector of two of the sites closest to a node is part of the medial axis
+    // when leaving the node in a direction, which is the case if the two sites stay closer than
+    // the other sites of the node.
+    fn leaves(&self, point: na::Point2<f64>, sites: &[usize], a: usize, b: usize, forward: bool) -> bool {
+        let Some((bisector, _)) = self.region.site_bisector(self.sites[a], self.sites[b]) else {
+            return false
+        };
+
+        let tangent = bisector.tangent(bisector.param(point));
+        let dir = if forward { tangent } else { -tangent };
+        let Some(dir) = na::Unit::try_new(dir, 0.0) else {
+            return false
+        };
+
+        let towards = |s: usize| self.region.site_domain_towards(self.sites[s], point, &dir);
+        if !towards(a) || !towards(b) {
+            return false
+        }
+
+        // How fast the distance to the sites grows along the bisector.
+        let growth = |s: usize| self.region.site_gradient(self.sites[s], point).dot(&dir);
+        let g = growth(a);
+
+        sites.iter()
+            .filter(|c| **c != a && **c != b)
+            .all(|c| !towards(*c) || growth(*c) > g + EPSILON * EPSILON)
+    }
+
+    // The point where a bisector traced from a parameter ends, where another site is as close as
+    // the two sites or where one of the sites leaves its domain.
+    fn next_node(&self, a: usize, b: usize, bisector: &Bisector, t0: f64, forward: bool) -> Option<na::Point2<f64>> {
+        let sign = if forward { 1.0 } else { -1.0 };
+        let mut best: Option<(f64, na::Point2<f64>)> = None;
+        let mut consider = |t: f64, p: na::Point2<f64>| {
+            let s = sign * (t - t0);
+            if s > EPSILON && best.is_none_or(|b| s < b.0) {
+                best = Some((s, p));
+            }
+        };
+
+        for c in 0..self.sites.len() {
+            if c == a || c == b {
+                continue
+            }
+
+            let triple = [self.sites[a], self.sites[b], self.sites[c]];
+            for (p, r) in self.region.equidistant_points(triple) {
+                let touching = triple.iter()
+                    .all(|s| self.region.site_distance(*s, p).is_some_and(|d| (d - r).abs() < EPSILON));
+                if r > EPSILON && touching {
+                    consider(bisector.param(p), p);
+                }
+            }
+        }
+
+        for s in [a, b] {
+            for (m, c) in self.region.site_domain_bounds(self.sites[s]) {
+                for t in bisector.solve(m, c) {
+                    consider(t, bisector.point(t));
+                }
+            }
+        }
+
+        best.map(|(_, p)| p)
+    }
+}
+
+impl Region2D {
+    /// Computes the medial axis of the region.
+    ///
+    /// The medial axis touches the boundary at the convex vertices of the region, where the
+    /// radius of the nodes is zero. Parallel edges give edges of the medial axis along the line
+    /// halfway between them, and edges of the medial axis around reflex vertices are parabolas.
+    pub fn medial_axis(&self) -> MedialAxis {
+        let mut tracer = Tracer {
+            region: self,
+            sites: self.medial_sites(),
+            axis: MedialAxis {
+                nodes: Vec::new(),
+                edges: Vec::new(),
+            },
+            node_ids: HashMap::new(),
+            traced: HashSet::new(),
+            stack: Vec::new(),
+        };
+
+        tracer.trace();
+        tracer.axis
+    }
+
+    // The edges and reflex vertices of the region.
+    fn medial_sites(&self) -> Vec<Site> {
+        let mut sites: Vec<_> = (0..self.edges().len()).map(Site::Edge).collect();
+        sites.extend((0..self.edges().len()).filter(|e| self.reflex_vertex(*e)).map(Site::Vertex));
+        sites
+    }
+
+    // Checks if the vertex at the end of an edge is reflex.
+    fn reflex_vertex(&self, e: usize) -> bool {
+        let n1 = self.edge_line(e).normal();
+        let n2 = self.edge_line(self.edge_next(e)).normal();
+        n1.perp(&n2) > 0.0
+    }
+
+    // The distance from a point to a site if the point is in the domain of the site, that is if
+    // the closest point of the site's part of the boundary is on the site.
+    fn site_distance(&self, site: Site, p: na::Point2<f64>) -> Option<f64> {
+        let inside = self.site_domain_bounds(site).iter().all(|(m, c)| m.dot(&p.coords) - c > -EPSILON);
+        let d = self.site_line_distance(site, p);
+        (inside && d > -EPSILON).then_some(d)
+    }
+
+    // The distance from a point to the line of an edge site or to a vertex site.
+    fn site_line_distance(&self, site: Site, p: na::Point2<f64>) -> f64 {
+        match site {
+            Site::Edge(e) => self.edge_line(e).project_point(p).dist,
+            Site::Vertex(e) => (self.edge_end_vertex(e) - p).norm(),
+        }
+    }
+
+    // The gradient of the distance to a site at a point in its domain.
+    fn site_gradient(&self, site: Site, p: na::Point2<f64>) -> na::Vector2<f64> {
+        match site {
+            Site::Edge(e) => self.edge_line(e).normal().into_inner(),
+            Site::Vertex(e) => (p - self.edge_end_vertex(e)).normalize(),
+        }
+    }
+
+    // The lines bounding the domain of a site, as `(m, c)` with `m.dot(x) >= c` in the domain.
+    // The domain of an edge is also bounded by its line.
+    fn site_domain_bounds(&self, site: Site) -> [(na::Vector2<f64>, f64); 2] {
+        match site {
+            Site::Edge(e) => {
+                let (p1, p2) = self.edge_points(e);
+                let dir = self.edge_line(e).dir().into_inner();
+                [(dir, dir.dot(&p1.coords)), (-dir, -dir.dot(&p2.coords))]
+            }
+            Site::Vertex(e) => {
+                let v = self.edge_end_vertex(e).coords;
+                let d1 = self.edge_line(e).dir().into_inner();
+                let d2 = self.edge_line(self.edge_next(e)).dir().into_inner();
+                [(d1, d1.dot(&v)), (-d2, -d2.dot(&v))]
+            }
+        }
+    }
+
+    // Checks if a point is in the domain of a site and stays in it when moving in a direction.
+    fn site_domain_towards(&self, site: Site, p: na::Point2<f64>, dir: &na::Vector2<f64>) -> bool {
+        self.site_distance(site, p).is_some()
+            && self.site_domain_bounds(site).iter()
+                .all(|(m, c)| m.dot(&p.coords) - c > EPSILON || m.dot(dir) > -EPSILON * EPSILON)
+    }
+
+    // The points with the same distance to the lines of three edge sites or to three vertex
+    // sites, together with the distance.
+    fn equidistant_points(&self, sites: [Site; 3]) -> Vec<(na::Point2<f64>, f64)> {
+        // Each edge gives a linear equation `n.dot(x) - r = d` in the point `x` and the distance
+        // `r`, and each vertex gives the equation `|x - v|^2 = r^2`. The differences between the
+        // equations of the vertices are linear.
+        let mut rows = Vec::new();
+        let mut vertex = None;
+
+        for site in sites {
+            match site {
+                Site::Edge(e) => {
+                    let line = self.edge_line(e);
+                    let n = line.normal().into_inner();
+                    rows.push((na::Vector3::new(n.x, n.y, -1.0), n.dot(&line.origin().coords)));
+                }
+                Site::Vertex(e) => {
+                    let v = self.edge_end_vertex(e);
+                    match vertex {
+                        None => vertex = Some(v),
+                        Some(v1) => {
+                            let m = (v - v1) * 2.0;
+                            rows.push((na::Vector3::new(m.x, m.y, 0.0), v.coords.norm_squared() - v1.coords.norm_squared()));
+                        }
+                    }
+                }
+            }
+        }
+
+        let Some(v) = vertex else {
+            let a = na::Matrix3::from_rows(&[rows[0].0.transpose(), rows[1].0.transpose(), rows[2].0.transpose()]);
+            let b = na::Vector3::new(rows[0].1, rows[1].1, rows[2].1);
+            return a.lu().solve(&b)
+                .map(|x| vec![(na::Point2::new(x.x, x.y), x.z)])
+                .unwrap_or_default()
+        };
+
+        // The solutions of the two linear equations are a line `x0 + s * dir`.
+        let (a1, a2) = (rows[0].0, rows[1].0);
+        let dir = a1.cross(&a2);
+        if dir.norm() < EPSILON {
+            return Vec::new()
+        }
+
+        let a = na::Matrix2::new(a1.dot(&a1), a1.dot(&a2), a2.dot(&a1), a2.dot(&a2));
+        let Some(y) = a.try_inverse().map(|inv| inv * na::Vector2::new(rows[0].1, rows[1].1)) else {
+            return Vec::new()
+        };
+        let x0 = a1 * y.x + a2 * y.y;
+
+        // The remaining equation is quadratic in `s`.
+        let p = na::Vector2::new(x0.x - v.x, x0.y - v.y);
+        let q = dir.xy();
+        let qa = q.norm_squared() - dir.z * dir.z;
+        let qb = 2.0 * (p.dot(&q) - x0.z * dir.z);
+        let qc = p.norm_squared() - x0.z * x0.z;
+
+        let roots = quadratic_roots(qa, qb, qc);
+
+        roots.into_iter()
+            .map(|s| {
+                let x = x0 + dir * s;
+                (na::Point2::new(x.x, x.y), x.z)
+            })
+            .collect()
+    }
+
+    // The bisector of two sites, if it can be part of the medial axis.
+    fn site_bisector(&self, s1: Site, s2: Site) -> Option<(Bisector, MedialCurve)> {
+        let line = |b: Bisector| Some((b, MedialCurve::Line));
+
+        match (s1, s2) {
+            (Site::Edge(e1), Site::Edge(e2)) => {
+                // Edges meeting at a reflex vertex are separated by the vertex.
+                if (self.edge_next(e1) == e2 && self.reflex_vertex(e1))
+                    || (self.edge_next(e2) == e1 && self.reflex_vertex(e2)) {
+                    return None
+                }
+
+                // The points with the same distance to the inside of both lines, which is the line
+                // halfway between them if they are parallel. Parallel lines with the same
+                // direction have no such points unless they coincide, and coinciding edges are
+                // never closest together as their domains do not overlap.
+                let (l1, l2) = (self.edge_line(e1), self.edge_line(e2));
+                let (n1, n2) = (l1.normal().into_inner(), l2.normal().into_inner());
+                line(Bisector::line(n1 - n2, n1.dot(&l1.origin().coords) - n2.dot(&l2.origin().coords))?)
+            }
+            (Site::Vertex(e1), Site::Vertex(e2)) => {
+                let (v1, v2) = (self.edge_end_vertex(e1), self.edge_end_vertex(e2));
+                line(Bisector::line((v2 - v1) * 2.0, v2.coords.norm_squared() - v1.coords.norm_squared())?)
+            }
+            (Site::Edge(e), Site::Vertex(v)) | (Site::Vertex(v), Site::Edge(e)) => {
+                // An edge and its own vertex are only separated by the normal at the vertex.
+                if e == v || e == self.edge_next(v) {
+                    return None
+                }
+
+                let focus = self.edge_end_vertex(v);
+                let directrix = self.edge_line(e);
+                if directrix.project_point(focus).dist < EPSILON {
+                    return None
+                }
+
+                Some((Bisector::Parabola { focus, directrix }, MedialCurve::Parabola { focus, directrix }))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::polygon;
+
+    fn find_node(axis: &MedialAxis, p: (f64, f64)) -> usize {
+        axis.nodes.iter()
+            .position(|n| (n.point - na::Point2::new(p.0, p.1)).norm() < 1e-6)
+            .unwrap()
+    }
+
+    fn connected(axis: &MedialAxis, a: usize, b: usize) -> bool {
+        axis.edges.iter().any(|e| e.nodes == (a, b) || e.nodes == (b, a))
+    }
+
+    #[test]
+    fn medial_axis_rectangle() {
+        let region = Region2D::from_polygons(&[polygon(&[(0.0, 0.0), (0.0, 2.0), (4.0, 2.0), (4.0, 0.0)])])
+            .unwrap();
+        let axis = region.medial_axis();
+
+        assert_eq!(axis.nodes.len(), 6);
+        assert_eq!(axis.edges.len(), 5);
+        assert!(axis.edges.iter().all(|e| matches!(e.curve, MedialCurve::Line)));
+
+        // The parallel long edges give the middle segment.
+        let (a, b) = (find_node(&axis, (1.0, 1.0)), find_node(&axis, (3.0, 1.0)));
+        assert!((axis.nodes[a].radius - 1.0).abs() < 1e-9);
+        assert!((axis.nodes[b].radius - 1.0).abs() < 1e-9);
+        assert!(connected(&axis, a, b));
+
+        let corner = find_node(&axis, (0.0, 0.0));
+        assert_eq!(axis.nodes[corner].radius, 0.0);
+        assert!(connected(&axis, a, corner));
+        assert!(!connected(&axis, b, corner));
+
+        // All four edges meet at the center of a square.
+        let square = Region2D::from_polygons(&[polygon(&[(0.0, 0.0), (0.0, 2.0), (2.0, 2.0), (2.0, 0.0)])])
+            .unwrap();
+        let axis = square.medial_axis();
+        assert_eq!(axis.nodes.len(), 5);
+        assert_eq!(axis.edges.len(), 4);
+    }
+
+    #[test]
+    fn medial_axis_reflex_vertex() {
+        let region = Region2D::from_polygons(&[polygon(&[
+            (0.0, 0.0), (0.0, 3.0), (1.0, 3.0), (1.0, 1.0), (3.0, 1.0), (3.0, 0.0),
+        ])]).unwrap();
+        let axis = region.medial_axis();
+
+        assert_eq!(axis.nodes.len(), 10);
+        assert_eq!(axis.edges.len(), 9);
+
+        // The node touching both edges at the corner and the reflex vertex.
+        let r = 2f64.sqrt() / (1.0 + 2f64.sqrt());
+        let center = find_node(&axis, (r, r));
+        assert!((axis.nodes[center].radius - r).abs() < 1e-9);
+
+        let arm = find_node(&axis, (1.0, 0.5));
+        let parabola = axis.edges.iter()
+            .position(|e| e.nodes == (center, arm) || e.nodes == (arm, center))
+            .unwrap();
+        assert!(matches!(axis.edges[parabola].curve, MedialCurve::Parabola { .. }));
+        assert_eq!(axis.edges.iter().filter(|e| matches!(e.curve, MedialCurve::Parabola { .. })).count(), 2);
+
+        // Points on the parabola are equally far from the reflex vertex and the bottom edge.
+        for t in [0.25, 0.5, 0.75] {
+            let p = axis.edge_point(parabola, t);
+            assert!(((p - na::Point2::new(1.0, 1.0)).norm() - p.y).abs() < 1e-9);
+            assert!(p.x > r - 1e-9 && p.x < 1.0 + 1e-9);
+        }
+    }
+
+    #[test]
+    fn medial_axis_hole() {
+        let region = Region2D::from_polygons(&[
+            polygon(&[(0.0, 0.0), (0.0, 3.0), (3.0, 3.0), (3.0, 0.0)]),
+            polygon(&[(1.0, 1.0), (2.0, 1.0), (2.0, 2.0), (1.0, 2.0)]),
+        ]).unwrap();
+        let axis = region.medial_axis();
+
+        // The axis goes around the hole, with parabolas at the corners of the hole.
+        assert_eq!(axis.edges.len(), axis.nodes.len());
+        assert!(axis.nodes.iter().all(|n| n.radius < 0.6));
+        assert!(axis.edges.iter().any(|e| matches!(e.curve, MedialCurve::Parabola { .. })));
+        assert!(!axis.nodes.iter().any(|n| region.signed_distance(n.point) > 1e-9));
+    }
+
+    #[test]
+    fn medial_axis_same_sites() {
+        // The parabola of the bottom tip of the hole and the bottom edge crosses the middle line of
+        // the strip twice, giving two nodes closest to the top and bottom edges and the tip.
+        let region = Region2D::from_polygons(&[
+            polygon(&[(0.0, 0.0), (0.0, 2.0), (10.0, 2.0), (10.0, 0.0)]),
+            polygon(&[(5.0, 1.5), (5.05, 1.64), (5.0, 1.78), (4.95, 1.64)]),
+        ]).unwrap();
+        let axis = region.medial_axis();
+
+        let d = 0.75f64.sqrt();
+        let (a, b) = (find_node(&axis, (5.0 - d, 1.0)), find_node(&axis, (5.0 + d, 1.0)));
+        assert_ne!(a, b);
+        assert!((axis.nodes[a].radius - 1.0).abs() < 1e-9);
+        assert!((axis.nodes[b].radius - 1.0).abs() < 1e-9);
+        assert_eq!(axis.edges.len(), axis.nodes.len());
+        assert!(axis.edges.iter().all(|e| e.nodes.0 != e.nodes.1));
+    }
+
+    #[test]
+    fn medial_axis_star() {
+        // A star with a convex vertex at each tip and a reflex vertex between the tips.
+        let n = 12;
+        let points: Vec<_> = (0..2 * n)
+            .map(|i| {
+                let a = -std::f64::consts::PI * i as f64 / n as f64;
+                let r = if i % 2 == 0 { 3.0 } else { 1.0 };
+                (r * a.cos(), r * a.sin())
+            })
+            .collect();
+        let region = Region2D::from_polygons(&[polygon(&points)]).unwrap();
+        let axis = region.medial_axis();
+
+        // The medial axis of a region without holes is a tree with a leaf at each tip.
+        assert_eq!(axis.edges.len(), axis.nodes.len() - 1);
+        let leaves = (0..axis.nodes.len())
+            .filter(|n| axis.edges.iter().filter(|e| e.nodes.0 == *n || e.nodes.1 == *n).count() == 1)
+            .count();
+        assert_eq!(leaves, n);
+
+        for node in &axis.nodes {
+            assert!((node.radius + region.signed_distance(node.point)).abs() < 1e-9);
+        }
+
+        let center = find_node(&axis, (0.0, 0.0));
+        assert!((axis.nodes[center].radius - region.signed_distance(na::Point2::origin()).abs()).abs() < 1e-9);
+        for e in 0..axis.edges.len() {
+            assert!(region.signed_distance(axis.edge_point(e, 0.5)) < 0.0);
+        }
+    }
+}
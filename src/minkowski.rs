@@ -0,0 +1,186 @@
+//! Minkowski sums and differences of regions with convex shapes.
+//!
+//! The Minkowski sum of a region with a convex polygon is computed from the convolution of the
+//! boundary of the region with the polygon. Each edge of the region is moved to the vertex of the
+//! polygon furthest out from the edge, and at each corner of the region the edges of the polygon
+//! between the two vertices are inserted. At reflex corners the polygon edges are inserted
+//! backwards, giving loops that are removed by the winding number, which is positive exactly on
+//! the sum.
+
+use crate::brep_2d::Region2D;
+use crate::offset::JoinType;
+use crate::winding;
+
+impl Region2D {
+    /// The Minkowski sum of the region with a convex polygon.
+    ///
+    /// The polygon is given by its vertices, in any order. Returns `None` if the polygon does not
+    /// have an area.
+    pub fn minkowski_sum(&self, polygon: &[na::Point2<f64>]) -> Option<Region2D> {
+        let polygon = convex_vertices(polygon)?;
+        let loops = self.loop_vertices();
+
+        winding::resolve_polygons(&convolution(&loops, &polygon), |w| w > 0)
+    }
+
+    /// The Minkowski difference of the region with a convex polygon, the points `p` such that
+    /// the polygon moved by `p` is inside the region.
+    ///
+    /// The polygon is given by its vertices, in any order. Returns `None` if the polygon does not
+    /// have an area.
+    pub fn minkowski_difference(&self, polygon: &[na::Point2<f64>]) -> Option<Region2D> {
+        let reflected: Vec<_> = polygon.iter().map(|p| -p).collect();
+        let polygon = convex_vertices(&reflected)?;
+
+        // The difference is the complement of the sum of the complement of the region with the
+        // reflected polygon. The reversed loops of the region have winding number minus one
+        // inside the region, and so the difference is where the winding number is negative.
+        let loops: Vec<_> = self.loop_vertices().into_iter()
+            .map(|l| l.into_iter().rev().collect())
+            .collect();
+
+        winding::resolve_polygons(&convolution(&loops, &polygon), |w| w < 0)
+    }
+
+    /// The Minkowski sum of the region with a disk centered at the origin.
+    ///
+    /// Approximated by [`JoinType::Round`] with `tolerance`, see [`Region2D::offset`].
+    pub fn minkowski_sum_disk(&self, radius: f64, tolerance: f64) -> Option<Region2D> {
+        self.offset(radius, JoinType::Round { tolerance })
+    }
+
+    /// The Minkowski difference of the region with a disk centered at the origin.
+    ///
+    /// Approximated by [`JoinType::Round`] with `tolerance`, see [`Region2D::offset`].
+    pub fn minkowski_difference_disk(&self, radius: f64, tolerance: f64) -> Option<Region2D> {
+        self.offset(-radius, JoinType::Round { tolerance })
+    }
+}
+
+// The vertices of the convex hull of a set of points in clockwise order.
+fn convex_vertices(points: &[na::Point2<f64>]) -> Option<Vec<na::Point2<f64>>> {
+    Region2D::from_convex_hull(points).loop_vertices().pop()
+}
+
+// The convolution of closed loops with a convex polygon in clockwise order.
+fn convolution(loops: &[Vec<na::Point2<f64>>], polygon: &[na::Point2<f64>]) -> Vec<Vec<na::Point2<f64>>> {
+    let m = polygon.len();
+
+    // The polygon vertex furthest out on the left hand side of an edge.
+    let support = |d: na::Vector2<f64>| {
+        let out = na::Vector2::new(-d.y, d.x);
+        (0..m).max_by(|a, b| out.dot(&polygon[*a].coords).total_cmp(&out.dot(&polygon[*b].coords))).unwrap()
+    };
+
+    loops.iter()
+        .map(|vs| {
+            let n = vs.len();
+            let dirs: Vec<_> = (0..n).map(|i| vs[(i + 1) % n] - vs[i]).collect();
+            let supports: Vec<_> = dirs.iter().map(|d| support(*d)).collect();
+
+            let mut points = Vec::new();
+
+            for i in 0..n {
+                let j = (i + 1) % n;
+                let v = vs[j];
+                let (a, b) = (supports[i], supports[j]);
+
+                points.push(vs[i] + polygon[a].coords);
+                points.push(v + polygon[a].coords);
+
+                // The support vertex moves clockwise around the polygon at clockwise turns and
+                // counterclockwise at counterclockwise turns.
+                let step = if dirs[i].perp(&dirs[j]) <= 0.0 { 1 } else { m - 1 };
+                let mut k = (a + step) % m;
+                while a != b && k != b {
+                    points.push(v + polygon[k].coords);
+                    k = (k + step) % m;
+                }
+            }
+
+            points
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{l_shape, polygon};
+
+    #[test]
+    fn minkowski_sum_square() {
+        let region = l_shape();
+        let square = polygon(&[(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)]);
+
+        let sum = region.minkowski_sum(&square).unwrap();
+        assert_eq!(sum.edges().len(), 6);
+        assert!((sum.area() - 12.0).abs() < 1e-9);
+
+        // A triangle that is not centered at the origin.
+        let triangle = polygon(&[(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]);
+        let sum = region.minkowski_sum(&triangle).unwrap();
+        assert!((sum.area() - 11.0).abs() < 1e-9);
+        assert!(sum.inside(na::Point2::new(3.5, 0.5)));
+        assert!(!sum.inside(na::Point2::new(-0.1, 1.0)));
+        assert!(sum.inside(na::Point2::new(1.9, 1.9)));
+        assert!(!sum.inside(na::Point2::new(2.5, 2.5)));
+
+        assert!(region.minkowski_sum(&polygon(&[(0.0, 0.0), (1.0, 1.0)])).is_none());
+    }
+
+    #[test]
+    fn minkowski_sum_closes_hole() {
+        let region = Region2D::from_polygons(&[
+            polygon(&[(0.0, 0.0), (0.0, 3.0), (3.0, 3.0), (3.0, 0.0)]),
+            polygon(&[(1.0, 1.0), (2.0, 1.0), (2.0, 2.0), (1.0, 2.0)]),
+        ]).unwrap();
+
+        let diamond = polygon(&[(0.0, -0.3), (0.3, 0.0), (0.0, 0.3), (-0.3, 0.0)]);
+        let sum = region.minkowski_sum(&diamond).unwrap();
+        assert_eq!(sum.loops().len(), 2);
+        assert!(!sum.inside(na::Point2::new(1.5, 1.5)));
+
+        let diamond = polygon(&[(0.0, -0.8), (0.8, 0.0), (0.0, 0.8), (-0.8, 0.0)]);
+        let sum = region.minkowski_sum(&diamond).unwrap();
+        assert_eq!(sum.loops().len(), 1);
+        assert!(sum.inside(na::Point2::new(1.5, 1.5)));
+    }
+
+    #[test]
+    fn minkowski_difference_square() {
+        let region = l_shape();
+        let square = polygon(&[(-0.25, -0.25), (0.25, -0.25), (0.25, 0.25), (-0.25, 0.25)]);
+
+        let difference = region.minkowski_difference(&square).unwrap();
+        assert_eq!(difference.edges().len(), 6);
+        assert!((difference.area() - 2.25).abs() < 1e-9);
+
+        // The tool does not fit in the arms of the L.
+        let large = polygon(&[(-0.6, -0.6), (0.6, -0.6), (0.6, 0.6), (-0.6, 0.6)]);
+        assert!(region.minkowski_difference(&large).unwrap().is_empty());
+
+        // The positions of a triangle with its corner at the reference point.
+        let triangle = polygon(&[(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]);
+        let difference = region.minkowski_difference(&triangle).unwrap();
+        assert!((difference.area() - 0.5).abs() < 1e-9);
+        assert!(difference.inside(na::Point2::new(0.1, 0.1)));
+        assert!(difference.inside(na::Point2::new(0.6, 0.3)));
+        assert!(!difference.inside(na::Point2::new(0.6, 0.6)));
+    }
+
+    #[test]
+    fn minkowski_disk() {
+        let region = l_shape();
+
+        let sum = region.minkowski_sum_disk(0.5, 1e-4).unwrap();
+        // The quarter disks at the five convex corners are added, and the strips along the edges
+        // overlap at the reflex corner.
+        let expected = 5.0 + 0.5 * 12.0 + 5.0 * 0.25 * std::f64::consts::PI / 4.0 - 0.25;
+        assert!((sum.area() - expected).abs() < 1e-3);
+
+        let difference = region.minkowski_difference_disk(0.25, 1e-4).unwrap();
+        assert!(difference.area() > 2.25);
+        assert!(difference.area() < 2.27);
+    }
+}
@@ -0,0 +1,221 @@
+use crate::brep_2d::Region2D;
+use crate::geometry::Line;
+use crate::winding;
+
+// Corners where the lines are closer to parallel than this are joined by intersecting the offset
+// lines directly.
+const MIN_CORNER_SIN: f64 = 1e-3;
+
+// The smallest angle between two lines of a round join.
+const MIN_ROUND_STEP: f64 = 1e-3;
+
+/// How the offset lines are joined at corners where they move apart.
+#[derive(Copy, Clone, Debug)]
+pub enum JoinType {
+    /// Extend the offset lines until they meet. If they would meet further than `limit` times the
+    /// offset distance from the corner the corner is cut off at that distance instead.
+    Miter { limit: f64 },
+    /// Approximate a circular arc around the corner. Regions only have straight edges, so the arc
+    /// is replaced by a polyline of tangent lines, which are at most `tolerance` from the arc.
+    /// A smaller tolerance gives more edges.
+    Round { tolerance: f64 },
+    /// Cut off the corner at the offset distance from the corner.
+    Square,
+}
+
+impl Region2D {
+    /// Offsets the region by a distance.
+    ///
+    /// Positive distances grow the region and negative distances shrink it. Edges that collapse
+    /// are removed and the region might split into several loops, or become empty.
+    pub fn offset(&self, distance: f64, join: JoinType) -> Option<Region2D> {
+        Some(self.offset_sourced(distance, join)?.0)
+    }
+
+    // Offsets the region, see `offset`, and returns the edge each edge of the result was offset
+    // from. The lines of a join belong to the edge before the corner.
+    pub(crate) fn offset_sourced(&self, distance: f64, join: JoinType) -> Option<(Region2D, Vec<usize>)> {
+        if distance == 0.0 {
+            return Some((self.clone(), (0..self.edges().len()).collect()))
+        }
+
+        let mut lines = Vec::new();
+        let mut line_edges = Vec::new();
+        let mut loops = Vec::new();
+
+        for edge_loop in self.loops() {
+            let mut offset_loop = Vec::new();
+
+            for (i, e) in edge_loop.iter().enumerate() {
+                let next = edge_loop[(i + 1) % edge_loop.len()];
+                let line = self.edge_line(*e);
+
+                let mut corner = vec![line.offset(-distance)];
+                corner.extend(corner_lines(line, self.edge_line(next), self.edge_end_vertex(*e), distance, join));
+
+                for line in corner {
+                    offset_loop.push(lines.len());
+                    lines.push(line);
+                    line_edges.push(*e);
+                }
+            }
+
+            loops.push(offset_loop);
+        }
+
+        let (region, sources) = winding::resolve_sourced(&lines, &loops, |w| w > 0)?;
+        Some((region, sources.into_iter().map(|l| line_edges[l]).collect()))
+    }
+}
+
+// The lines between the offset lines of two edges meeting at the vertex `v`.
+fn corner_lines(l1: Line, l2: Line, v: na::Point2<f64>, distance: f64, join: JoinType) -> Vec<Line> {
+    let n1 = l1.normal().into_inner();
+    let n2 = l2.normal().into_inner();
+    let sin = n1.perp(&n2);
+    let cos = n1.dot(&n2);
+
+    if sin.abs() < MIN_CORNER_SIN && cos > 0.0 {
+        return Vec::new()
+    }
+
+    // Convex corners turn right, as the inside of the region is on the right.
+    if distance * sin > 0.0 {
+        // The offset lines overlap, so we connect them through the corner. The resulting loops
+        // are removed when the winding number is resolved.
+        return vec![
+            Line::from_point_dir(v, n1 * distance),
+            Line::from_point_dir(v, -n2 * distance),
+        ]
+    }
+
+    let bisector = na::Unit::new_normalize(n1 + n2);
+    let square = |dist: f64| Line::from_point_normal(v - bisector.into_inner() * dist * distance.signum(), bisector);
+
+    match join {
+        JoinType::Miter { limit } => {
+            let cos_half = ((1.0 + cos) / 2.0).sqrt();
+            if cos_half * limit >= 1.0 {
+                Vec::new()
+            } else {
+                vec![square(limit * distance.abs())]
+            }
+        }
+        JoinType::Square => vec![square(distance.abs())],
+        JoinType::Round { tolerance } => arc_lines(v, distance, n1, sin.atan2(cos), tolerance),
+    }
+}
+
+// Lines approximating an arc of a circle by tangents, where the normals of the tangents are `n1`
+// rotated by up to `angle`. The lines at the ends of the arc are not included.
+//
+// The circle is on the inside of the lines if the radius is positive, and on the outside if it is
+// negative. The lines are at most `tolerance` from the circle.
+pub(crate) fn arc_lines(
+    center: na::Point2<f64>,
+    radius: f64,
+    n1: na::Vector2<f64>,
+    angle: f64,
+    tolerance: f64,
+) -> Vec<Line> {
+    let max_step = 2.0 * (radius.abs() / (radius.abs() + tolerance)).acos();
+    let steps = (angle.abs() / max_step.max(MIN_ROUND_STEP))
+        .ceil()
+        .min(angle.abs() / MIN_ROUND_STEP) as usize;
+
+    (1..steps)
+        .map(|i| {
+            let n = na::Rotation2::new(angle * i as f64 / steps as f64) * n1;
+            Line::from_point_normal(center - n * radius, na::Unit::new_normalize(n))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{l_shape, polygons, square};
+
+    const MITER: JoinType = JoinType::Miter { limit: 2.0 };
+
+    #[test]
+    fn offset_square_joins() {
+        let region = square();
+
+        let miter = region.offset(1.0, MITER).unwrap();
+        assert_eq!(miter.edges().len(), 4);
+        assert!((miter.area() - 25.0).abs() < 1e-9);
+
+        let square = region.offset(1.0, JoinType::Square).unwrap();
+        assert_eq!(square.edges().len(), 8);
+        assert!((square.area() - (21.0 + 4.0 * (1.0 - (2f64.sqrt() - 1.0).powi(2)))).abs() < 1e-9);
+
+        let round = region.offset(1.0, JoinType::Round { tolerance: 1e-3 }).unwrap();
+        assert!(round.area() > 21.0 + std::f64::consts::PI);
+        assert!(round.area() < 21.0 + std::f64::consts::PI + 0.01);
+        assert!(round.inside(na::Point2::new(-0.7, -0.7)));
+        assert!(!round.inside(na::Point2::new(-0.72, -0.72)));
+    }
+
+    #[test]
+    fn offset_inward_collapses() {
+        let region = square();
+
+        let inner = region.offset(-1.0, MITER).unwrap();
+        assert!((inner.area() - 1.0).abs() < 1e-9);
+
+        let round = region.offset(-1.0, JoinType::Round { tolerance: 1e-3 }).unwrap();
+        assert_eq!(round.edges().len(), 4);
+
+        assert!(region.offset(-1.6, MITER).unwrap().is_empty());
+    }
+
+    #[test]
+    fn offset_concave() {
+        let region = l_shape();
+
+        let outer = region.offset(0.5, MITER).unwrap();
+        assert_eq!(outer.edges().len(), 6);
+        assert!((outer.area() - 12.0).abs() < 1e-9);
+
+        let inner = region.offset(-0.25, MITER).unwrap();
+        assert_eq!(inner.edges().len(), 6);
+        assert!((inner.area() - 2.25).abs() < 1e-9);
+
+        let round = region.offset(-0.25, JoinType::Round { tolerance: 1e-3 }).unwrap();
+        assert!(round.area() > 2.25);
+        assert!(round.area() < 2.25 + (1.0 - std::f64::consts::PI / 4.0) * 0.0625 + 1e-3);
+    }
+
+    #[test]
+    fn offset_hole_closes() {
+        let region = polygons(&[
+            &[(0.0, 0.0), (0.0, 3.0), (3.0, 3.0), (3.0, 0.0)],
+            &[(1.0, 1.0), (2.0, 1.0), (2.0, 2.0), (1.0, 2.0)],
+        ]);
+
+        let small = region.offset(0.25, MITER).unwrap();
+        assert_eq!(small.loops().len(), 2);
+        assert!((small.area() - 12.0).abs() < 1e-9);
+
+        let closed = region.offset(0.6, MITER).unwrap();
+        assert_eq!(closed.loops().len(), 1);
+        assert!((closed.area() - 4.2 * 4.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn offset_splits_region() {
+        let region = polygons(&[&[
+            (0.0, 0.0), (0.0, 2.0), (2.0, 2.0), (2.0, 1.2), (4.0, 1.2),
+            (4.0, 2.0), (6.0, 2.0), (6.0, 0.0), (4.0, 0.0), (4.0, 0.8),
+            (2.0, 0.8), (2.0, 0.0),
+        ]]);
+
+        let split = region.offset(-0.3, MITER).unwrap();
+        assert_eq!(split.loops().len(), 2);
+        assert!((split.area() - 2.0 * 1.4 * 1.4).abs() < 1e-9);
+        assert!(split.inside(na::Point2::new(1.0, 1.0)));
+        assert!(!split.inside(na::Point2::new(3.0, 1.0)));
+        assert!(split.inside(na::Point2::new(5.0, 1.0)));
+    }
+}
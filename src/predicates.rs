@@ -0,0 +1,188 @@
+//! Robust geometric predicates.
+//!
+//! The predicates are first evaluated with normal floating point arithmetic. If the result is too
+//! close to zero to be trusted they are evaluated exactly using floating point expansions, see
+//! Shewchuk, "Adaptive Precision Floating-Point Arithmetic and Fast Robust Geometric Predicates".
+
+const EPS: f64 = f64::EPSILON / 2.0;
+const CCW_ERRBOUND: f64 = (3.0 + 16.0 * EPS) * EPS;
+const ICC_ERRBOUND: f64 = (10.0 + 96.0 * EPS) * EPS;
+
+/// Orientation of the point `c` relative to the line through `a` and `b`.
+///
+/// Positive if `a`, `b` and `c` are in counterclockwise order, negative if they are in clockwise
+/// order and zero if they are colinear. The sign of the result is always exact.
+pub fn orient2d(a: na::Point2<f64>, b: na::Point2<f64>, c: na::Point2<f64>) -> f64 {
+    let detleft = (a.x - c.x) * (b.y - c.y);
+    let detright = (a.y - c.y) * (b.x - c.x);
+    let det = detleft - detright;
+
+    let detsum = detleft.abs() + detright.abs();
+    if det.abs() > CCW_ERRBOUND * detsum {
+        return det
+    }
+
+    let acx = two_diff(a.x, c.x);
+    let bcx = two_diff(b.x, c.x);
+    let acy = two_diff(a.y, c.y);
+    let bcy = two_diff(b.y, c.y);
+
+    let det = expansion_diff(&expansion_product(&acx, &bcy), &expansion_product(&acy, &bcx));
+    estimate(&det)
+}
+
+/// Checks if the point `d` is inside the circle through `a`, `b` and `c`.
+///
+/// Positive if `d` is inside the circle, negative if it is outside and zero if it is on the
+/// circle. The points `a`, `b` and `c` must be in counterclockwise order, otherwise the sign is
+/// reversed. The sign of the result is always exact.
+pub fn incircle(a: na::Point2<f64>, b: na::Point2<f64>, c: na::Point2<f64>, d: na::Point2<f64>) -> f64 {
+    let adx = a.x - d.x;
+    let bdx = b.x - d.x;
+    let cdx = c.x - d.x;
+    let ady = a.y - d.y;
+    let bdy = b.y - d.y;
+    let cdy = c.y - d.y;
+
+    let alift = adx * adx + ady * ady;
+    let blift = bdx * bdx + bdy * bdy;
+    let clift = cdx * cdx + cdy * cdy;
+
+    let det = alift * (bdx * cdy - cdx * bdy)
+        + blift * (cdx * ady - adx * cdy)
+        + clift * (adx * bdy - bdx * ady);
+
+    let permanent = alift * ((bdx * cdy).abs() + (cdx * bdy).abs())
+        + blift * ((cdx * ady).abs() + (adx * cdy).abs())
+        + clift * ((adx * bdy).abs() + (bdx * ady).abs());
+
+    if det.abs() > ICC_ERRBOUND * permanent {
+        return det
+    }
+
+    let adx = two_diff(a.x, d.x);
+    let bdx = two_diff(b.x, d.x);
+    let cdx = two_diff(c.x, d.x);
+    let ady = two_diff(a.y, d.y);
+    let bdy = two_diff(b.y, d.y);
+    let cdy = two_diff(c.y, d.y);
+
+    let lift = |x: &[f64], y: &[f64]| expansion_sum(&expansion_product(x, x), &expansion_product(y, y));
+    let cross = |x1: &[f64], y1: &[f64], x2: &[f64], y2: &[f64]| {
+        expansion_diff(&expansion_product(x1, y2), &expansion_product(x2, y1))
+    };
+
+    let a_term = expansion_product(&lift(&adx, &ady), &cross(&bdx, &bdy, &cdx, &cdy));
+    let b_term = expansion_product(&lift(&bdx, &bdy), &cross(&cdx, &cdy, &adx, &ady));
+    let c_term = expansion_product(&lift(&cdx, &cdy), &cross(&adx, &ady, &bdx, &bdy));
+
+    let det = expansion_sum(&expansion_sum(&a_term, &b_term), &c_term);
+    estimate(&det)
+}
+
+// An expansion is a sum of non-overlapping floating point numbers sorted by increasing magnitude.
+
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let x = a + b;
+    let bv = x - a;
+    let av = x - bv;
+    (x, (a - av) + (b - bv))
+}
+
+fn two_diff(a: f64, b: f64) -> [f64; 2] {
+    let (x, y) = two_sum(a, -b);
+    [y, x]
+}
+
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let x = a * b;
+    (x, a.mul_add(b, -x))
+}
+
+fn grow_expansion(e: &[f64], b: f64) -> Vec<f64> {
+    let mut h = Vec::with_capacity(e.len() + 1);
+    let mut q = b;
+    for &x in e {
+        let (sum, err) = two_sum(q, x);
+        if err != 0.0 {
+            h.push(err);
+        }
+        q = sum;
+    }
+    h.push(q);
+    h
+}
+
+fn expansion_sum(e: &[f64], f: &[f64]) -> Vec<f64> {
+    f.iter().fold(e.to_vec(), |h, &x| grow_expansion(&h, x))
+}
+
+fn expansion_diff(e: &[f64], f: &[f64]) -> Vec<f64> {
+    let neg: Vec<_> = f.iter().map(|x| -x).collect();
+    expansion_sum(e, &neg)
+}
+
+fn scale_expansion(e: &[f64], b: f64) -> Vec<f64> {
+    let mut h = Vec::with_capacity(2 * e.len());
+    let mut q = 0.0;
+    for &x in e {
+        let (product, product_err) = two_product(x, b);
+        let (sum, err) = two_sum(q, product_err);
+        if err != 0.0 {
+            h.push(err);
+        }
+        let (sum, err) = two_sum(product, sum);
+        if err != 0.0 {
+            h.push(err);
+        }
+        q = sum;
+    }
+    h.push(q);
+    h
+}
+
+fn expansion_product(e: &[f64], f: &[f64]) -> Vec<f64> {
+    f.iter().fold(vec![0.0], |h, &x| expansion_sum(&h, &scale_expansion(e, x)))
+}
+
+// The largest component of an expansion has the same sign as the whole expansion.
+fn estimate(e: &[f64]) -> f64 {
+    e.iter().rev().copied().find(|x| *x != 0.0).unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orient2d_colinear_exact() {
+        let b = na::Point2::new(12.0, 12.0);
+        let c = na::Point2::new(24.0, 24.0);
+
+        for i in 0..1000 {
+            let p = na::Point2::new(f64::from_bits(0.5f64.to_bits() + i), 0.5);
+            let exact = orient2d(p, b, c);
+
+            if i == 0 {
+                assert_eq!(exact, 0.0);
+                assert_eq!(orient2d(c, b, p), 0.0);
+            } else {
+                assert!(exact < 0.0);
+                assert!(orient2d(b, c, p) < 0.0);
+                assert!(orient2d(c, b, p) > 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn incircle_cocircular_exact() {
+        let a = na::Point2::new(1.0, 0.0);
+        let b = na::Point2::new(0.0, 1.0);
+        let c = na::Point2::new(-1.0, 0.0);
+
+        assert_eq!(incircle(a, b, c, na::Point2::new(0.0, -1.0)), 0.0);
+        assert!(incircle(a, b, c, na::Point2::new(0.0, f64::from_bits((-1.0f64).to_bits() - 1))) > 0.0);
+        assert!(incircle(a, b, c, na::Point2::new(0.0, f64::from_bits((-1.0f64).to_bits() + 1))) < 0.0);
+        assert!(incircle(a, c, b, na::Point2::new(0.0, 0.0)) < 0.0);
+    }
+}
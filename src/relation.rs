@@ -0,0 +1,217 @@
+//! Relations between regions, boolean operations and gluing of connecting regions.
+//!
+//! Two regions either intersect, connect or are disjoint. Regions connect if they do not
+//! intersect but share a part of their boundary, where the shared edges of the two regions have
+//! opposite directions. Gluing connecting regions cancels the shared edges, which is the union of
+//! the regions as computed by the winding number.
+//!
+//! Boolean operations are computed from the winding number of the loops of both regions, which is
+//! two inside both regions. For the difference the loops of the second region are reversed, so
+//! the winding number is one only inside the first region.
+
+use crate::brep_2d::Region2D;
+use crate::geometry::{Line, EPSILON};
+use crate::winding;
+
+/// The relation between two regions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Relation {
+    /// The regions have no points in common. Regions touching only at vertices are disjoint.
+    Disjoint,
+    /// The insides of the regions overlap.
+    Intersect,
+    /// The regions do not overlap but share a part of their boundary.
+    Connect,
+}
+
+/// A boolean operation on two regions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BooleanOp {
+    Union,
+    Intersection,
+    /// The first region with the second region removed.
+    Difference,
+}
+
+impl Region2D {
+    /// Computes a boolean operation of two regions.
+    ///
+    /// Returns `None` if the result can not be represented as a region, for example if it
+    /// consists of two parts touching at a vertex.
+    pub fn boolean(&self, other: &Region2D, op: BooleanOp) -> Option<Region2D> {
+        Some(self.boolean_sourced(other, op)?.0)
+    }
+
+    // Computes a boolean operation of two regions, see `boolean`, together with the edge each
+    // edge of the result lies on, where the edges of `other` come after the edges of `self`.
+    pub(crate) fn boolean_sourced(&self, other: &Region2D, op: BooleanOp) -> Option<(Region2D, Vec<usize>)> {
+        match op {
+            BooleanOp::Union => {
+                let (lines, loops) = combined_loops(self, other, false);
+                winding::resolve_sourced(&lines, &loops, |w| w >= 1)
+            }
+            BooleanOp::Intersection => {
+                let (lines, loops) = combined_loops(self, other, false);
+                winding::resolve_sourced(&lines, &loops, |w| w >= 2)
+            }
+            BooleanOp::Difference => {
+                let (lines, loops) = combined_loops(self, other, true);
+                winding::resolve_sourced(&lines, &loops, |w| w >= 1)
+            }
+        }
+    }
+
+    /// Classifies the relation between two regions.
+    pub fn relation(&self, other: &Region2D) -> Relation {
+        if self.overlaps(other) {
+            Relation::Intersect
+        } else if self.shares_boundary(other) {
+            Relation::Connect
+        } else {
+            Relation::Disjoint
+        }
+    }
+
+    /// Glues two connecting regions into one region, removing the shared edges.
+    ///
+    /// Returns `None` if the regions do not connect.
+    pub fn glue(&self, other: &Region2D) -> Option<Region2D> {
+        if self.relation(other) != Relation::Connect {
+            return None
+        }
+
+        self.boolean(other, BooleanOp::Union)
+    }
+
+    // Checks if the insides of two regions overlap, which is where the winding number of the
+    // combined loops is two.
+    fn overlaps(&self, other: &Region2D) -> bool {
+        if self.is_empty() || other.is_empty() {
+            return false
+        }
+
+        // The overlap may not be representable as a region, for example if it consists of two
+        // parts touching at a vertex, but it is then not empty.
+        self.boolean(other, BooleanOp::Intersection)
+            .is_none_or(|r| r.area() > EPSILON)
+    }
+
+    // Checks if an edge of one region lies on an edge of the other region with the opposite
+    // direction.
+    fn shares_boundary(&self, other: &Region2D) -> bool {
+        (0..self.edges().len()).any(|a| {
+            let line = self.edge_line(a);
+            (0..other.edges().len())
+                .filter(|b| line.coincident(&other.edge_line(*b).flip()))
+                .any(|b| {
+                    let (shared, _) = self.edge_region(a).split(&other.edge_region(b).flip());
+                    let points = shared.points();
+                    (0..points.len()).step_by(2).any(|i| points[i + 1].pos - points[i].pos > EPSILON)
+                })
+        })
+    }
+}
+
+// The lines and loops of two regions together, see `Region2D::line_loops`, optionally with the
+// loops of the second region reversed.
+fn combined_loops(r1: &Region2D, r2: &Region2D, reverse: bool) -> (Vec<Line>, Vec<Vec<usize>>) {
+    let (mut lines, mut loops) = r1.line_loops();
+    let (lines2, loops2) = r2.line_loops();
+    let offset = lines.len();
+
+    for mut l in loops2 {
+        if reverse {
+            l.reverse();
+        }
+        loops.push(l.into_iter().map(|e| e + offset).collect());
+    }
+
+    if reverse {
+        lines.extend(lines2.iter().map(|l| l.flip()));
+    } else {
+        lines.extend(lines2);
+    }
+
+    (lines, loops)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::rect;
+
+    #[test]
+    fn region_relations() {
+        let a = rect(0.0, 0.0, 2.0, 2.0);
+
+        assert_eq!(a.relation(&rect(3.0, 0.0, 4.0, 2.0)), Relation::Disjoint);
+        assert_eq!(a.relation(&rect(2.0, 2.0, 3.0, 3.0)), Relation::Disjoint);
+        assert_eq!(a.relation(&rect(1.0, 1.0, 3.0, 3.0)), Relation::Intersect);
+        assert_eq!(a.relation(&rect(0.5, 0.5, 1.5, 1.5)), Relation::Intersect);
+        assert_eq!(a.relation(&a), Relation::Intersect);
+        assert_eq!(a.relation(&rect(2.0, 0.0, 4.0, 2.0)), Relation::Connect);
+        assert_eq!(a.relation(&rect(2.0, 1.0, 3.0, 3.0)), Relation::Connect);
+        assert_eq!(a.relation(&Region2D::empty()), Relation::Disjoint);
+    }
+
+    #[test]
+    fn boolean_operations() {
+        let a = rect(0.0, 0.0, 2.0, 2.0);
+        let b = rect(1.0, 1.0, 3.0, 3.0);
+
+        let union = a.boolean(&b, BooleanOp::Union).unwrap();
+        assert!((union.area() - 7.0).abs() < 1e-9);
+
+        let intersection = a.boolean(&b, BooleanOp::Intersection).unwrap();
+        assert!((intersection.area() - 1.0).abs() < 1e-9);
+
+        let difference = a.boolean(&b, BooleanOp::Difference).unwrap();
+        assert!((difference.area() - 3.0).abs() < 1e-9);
+        assert!(difference.inside(na::Point2::new(0.5, 0.5)));
+        assert!(!difference.inside(na::Point2::new(1.5, 1.5)));
+
+        let hole = a.boolean(&rect(0.5, 0.5, 1.5, 1.5), BooleanOp::Difference).unwrap();
+        assert_eq!(hole.loops().len(), 2);
+        assert!((hole.area() - 3.0).abs() < 1e-9);
+
+        assert!(a.boolean(&rect(3.0, 0.0, 4.0, 2.0), BooleanOp::Intersection).unwrap().is_empty());
+    }
+
+    #[test]
+    fn glue_regions() {
+        let a = rect(0.0, 0.0, 2.0, 2.0);
+
+        let glued = a.glue(&rect(2.0, 0.0, 4.0, 2.0)).unwrap();
+        assert_eq!(glued.edges().len(), 4);
+        assert!((glued.area() - 8.0).abs() < 1e-9);
+
+        // An L shape from a partially shared edge.
+        let glued = a.glue(&rect(2.0, 1.0, 3.0, 3.0)).unwrap();
+        assert_eq!(glued.edges().len(), 8);
+        assert!((glued.area() - 6.0).abs() < 1e-9);
+        assert!(glued.inside(na::Point2::new(2.5, 2.5)));
+
+        // A part filling a hole glues to a solid region.
+        let ring = Region2D::from_polygons(&[
+            vec![
+                na::Point2::new(-1.0, -1.0),
+                na::Point2::new(-1.0, 3.0),
+                na::Point2::new(3.0, 3.0),
+                na::Point2::new(3.0, -1.0),
+            ],
+            vec![
+                na::Point2::new(2.0, 0.0),
+                na::Point2::new(2.0, 2.0),
+                na::Point2::new(0.0, 2.0),
+                na::Point2::new(0.0, 0.0),
+            ],
+        ]).unwrap();
+        assert_eq!(ring.relation(&a), Relation::Connect);
+        let glued = ring.glue(&a).unwrap();
+        assert_eq!(glued.loops().len(), 1);
+        assert!((glued.area() - 16.0).abs() < 1e-9);
+
+        assert!(a.glue(&rect(1.0, 1.0, 3.0, 3.0)).is_none());
+        assert!(a.glue(&rect(3.0, 0.0, 4.0, 2.0)).is_none());
+    }
+}
@@ -0,0 +1,658 @@
+//! Straight skeletons of regions.
+//!
+//! The straight skeleton is traced by the corners of the wavefront, the boundary of the region
+//! with all edges moving inwards at unit speed. The wavefront is stored as loops of edges of the
+//! region, where the line of each wavefront edge at time `t` is the line of the region edge
+//! offset by `t`. The wavefront changes at two kinds of events:
+//!
+//! - Edge events, where an edge shrinks to nothing and its neighbours become adjacent.
+//! - Split events, where a reflex corner runs into an edge and splits its loop in two, or joins
+//!   two loops.
+//!
+//! When two edges of a loop become adjacent with opposite directions the wavefront has collapsed
+//! into a sliver of zero width, and the sliver is zipped up along the common line of the edges.
+
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::brep_2d::Region2D;
+use crate::geometry::EPSILON;
+
+/// The straight skeleton of a region.
+#[derive(Clone, Debug)]
+pub struct StraightSkeleton {
+    pub nodes: Vec<SkeletonNode>,
+    pub arcs: Vec<SkeletonArc>,
+}
+
+/// A node of the straight skeleton.
+#[derive(Copy, Clone, Debug)]
+pub struct SkeletonNode {
+    pub point: na::Point2<f64>,
+    /// The offset time, the time at which the wavefront reaches the node. This is zero at the
+    /// vertices of the region.
+    pub time: f64,
+}
+
+/// An arc of the straight skeleton, traced by a corner of the wavefront.
+#[derive(Copy, Clone, Debug)]
+pub struct SkeletonArc {
+    /// The node at the start of the arc and the node at the end of the arc. The start node has
+    /// the earlier time.
+    pub nodes: (usize, usize),
+    /// The edge of the region before the corner and the edge after the corner.
+    pub edges: (usize, usize),
+}
+
+impl Region2D {
+    /// Computes the straight skeleton of the region.
+    ///
+    /// Returns `None` if the wavefront degenerates in a way that can not be resolved.
+    pub fn straight_skeleton(&self) -> Option<StraightSkeleton> {
+        let mut wavefront = Wavefront::new(self)?;
+        wavefront.advance(f64::INFINITY);
+
+        if wavefront.corners.iter().all(|c| !c.alive) {
+            Some(wavefront.skeleton)
+        } else {
+            None
+        }
+    }
+
+    /// The wavefront of the straight skeleton at a time, which is the region shrunk by `time`
+    /// with mitered corners.
+    ///
+    /// Returns `None` if the time is negative, or if the wavefront touches itself at the time.
+    pub fn skeleton_offset(&self, time: f64) -> Option<Region2D> {
+        if time < 0.0 {
+            return None
+        }
+
+        let mut wavefront = Wavefront::new(self)?;
+        wavefront.advance(time);
+
+        let lines = (0..self.edges().len())
+            .map(|e| self.edge_line(e).offset(time))
+            .collect();
+
+        Self::from_loops(lines, &wavefront.loops())
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Corner {
+    // The node the corner started at, and the position and time of that node.
+    node: usize,
+    point: na::Point2<f64>,
+    time: f64,
+    // The velocity of the corner, `None` if the edges have opposite directions.
+    velocity: Option<na::Vector2<f64>>,
+    edges: (usize, usize),
+    // The neighbouring corners along the wavefront, the wavefront edge between `prev` and this
+    // corner is on `edges.0`.
+    prev: usize,
+    next: usize,
+    alive: bool,
+}
+
+impl Corner {
+    fn position(&self, time: f64) -> na::Point2<f64> {
+        self.point + self.velocity.unwrap_or_else(na::Vector2::zeros) * (time - self.time)
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum Event {
+    // The wavefront edge between two corners shrinks to nothing.
+    Edge {
+        prev: usize,
+        corner: usize,
+    },
+    // A reflex corner runs into the moving line of an edge of the region.
+    Split {
+        corner: usize,
+        edge: usize,
+    },
+}
+
+// An event in a queue, ordered such that the earliest event is the greatest.
+#[derive(Copy, Clone, Debug)]
+struct QueuedEvent {
+    time: f64,
+    event: Event,
+}
+
+impl PartialEq for QueuedEvent {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for QueuedEvent {}
+
+impl PartialOrd for QueuedEvent {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedEvent {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other.time.total_cmp(&self.time)
+    }
+}
+
+struct Wavefront<'a> {
+    region: &'a Region2D,
+    time: f64,
+    // All corners that have been part of the wavefront, which form loops through `prev` and
+    // `next` while they are alive.
+    corners: Vec<Corner>,
+    // The corners at the end of the wavefront edges on each edge of the region.
+    edge_corners: Vec<Vec<usize>>,
+    // The events of the corners, which are dropped when they are taken from the queue if their
+    // corners are no longer part of the wavefront.
+    edge_events: BinaryHeap<QueuedEvent>,
+    split_events: BinaryHeap<QueuedEvent>,
+    // The nodes by their point rounded to a grid of size `EPSILON`.
+    node_cells: HashMap<(i64, i64), Vec<usize>>,
+    skeleton: StraightSkeleton,
+}
+
+impl<'a> Wavefront<'a> {
+    fn new(region: &'a Region2D) -> Option<Self> {
+        let mut wavefront = Self {
+            region,
+            time: 0.0,
+            corners: Vec::new(),
+            edge_corners: vec![Vec::new(); region.edges().len()],
+            edge_events: BinaryHeap::new(),
+            split_events: BinaryHeap::new(),
+            node_cells: HashMap::new(),
+            skeleton: StraightSkeleton {
+                nodes: Vec::new(),
+                arcs: Vec::new(),
+            },
+        };
+
+        for edges in region.loops() {
+            let n = edges.len();
+            let first = wavefront.corners.len();
+
+            for i in 0..n {
+                let node = wavefront.add_node(region.edge_end_vertex(edges[i]), 0.0);
+                let corner = wavefront.corner(node, edges[i], edges[(i + 1) % n], first + (i + n - 1) % n, first + (i + 1) % n);
+                corner.velocity?;
+                wavefront.edge_corners[corner.edges.0].push(wavefront.corners.len());
+                wavefront.corners.push(corner);
+            }
+        }
+
+        for c in 0..wavefront.corners.len() {
+            wavefront.schedule(c);
+        }
+
+        Some(wavefront)
+    }
+
+    fn add_node(&mut self, point: na::Point2<f64>, time: f64) -> usize {
+        let cell = ((point.x / EPSILON).floor() as i64, (point.y / EPSILON).floor() as i64);
+        let nodes = &self.skeleton.nodes;
+        let existing = (cell.0 - 1..=cell.0 + 1)
+            .flat_map(|x| (cell.1 - 1..=cell.1 + 1).map(move |y| (x, y)))
+            .filter_map(|c| self.node_cells.get(&c))
+            .flatten()
+            .find(|n| (nodes[**n].time - time).abs() < EPSILON && (nodes[**n].point - point).norm() < EPSILON);
+
+        if let Some(n) = existing {
+            return *n
+        }
+
+        let n = self.skeleton.nodes.len();
+        self.skeleton.nodes.push(SkeletonNode { point, time });
+        self.node_cells.entry(cell).or_default().push(n);
+        n
+    }
+
+    fn add_arc(&mut self, from: usize, to: usize, edges: (usize, usize)) {
+        if from != to {
+            self.skeleton.arcs.push(SkeletonArc {
+                nodes: (from, to),
+                edges,
+            });
+        }
+    }
+
+    // A new corner between two edges at a node.
+    fn corner(&self, node: usize, e1: usize, e2: usize, prev: usize, next: usize) -> Corner {
+        let n1 = self.region.edge_line(e1).normal().into_inner();
+        let n2 = self.region.edge_line(e2).normal().into_inner();
+        let det = n1.perp(&n2);
+
+        // The corner moves such that both lines move at unit speed.
+        let velocity = if det.abs() >= EPSILON {
+            Some(na::Vector2::new(n2.y - n1.y, n1.x - n2.x) / det)
+        } else if n1.dot(&n2) > 0.0 {
+            Some(n1)
+        } else {
+            None
+        };
+
+        let node_ref = &self.skeleton.nodes[node];
+        Corner {
+            node,
+            point: node_ref.point,
+            time: node_ref.time,
+            velocity,
+            edges: (e1, e2),
+            prev,
+            next,
+            alive: true,
+        }
+    }
+
+    // Adds a corner to the wavefront between two corners, or as the only corner of a loop if
+    // `link` is `None`.
+    fn insert(&mut self, node: usize, edges: (usize, usize), link: Option<(usize, usize)>) -> usize {
+        let c = self.corners.len();
+        let (prev, next) = link.unwrap_or((c, c));
+        let corner = self.corner(node, edges.0, edges.1, prev, next);
+        self.corners.push(corner);
+        self.corners[prev].next = c;
+        self.corners[next].prev = c;
+        self.edge_corners[edges.0].push(c);
+        c
+    }
+
+    // Ends the path of a corner at a node and removes it from the wavefront.
+    fn finish(&mut self, c: usize, node: usize) {
+        let corner = &mut self.corners[c];
+        corner.alive = false;
+        let (from, edges) = (corner.node, corner.edges);
+        self.add_arc(from, node, edges);
+    }
+
+    // Adds the events of a corner to the queues.
+    fn schedule(&mut self, c: usize) {
+        let corner = self.corners[c];
+
+        for (prev, next) in [(corner.prev, c), (c, corner.next)] {
+            if let Some(time) = self.collapse_time(prev, next) {
+                self.edge_events.push(QueuedEvent {
+                    time,
+                    event: Event::Edge { prev, corner: next },
+                });
+            }
+        }
+
+        let (e1, e2) = corner.edges;
+        if self.region.edge_line(e1).normal().perp(&self.region.edge_line(e2).normal()) < EPSILON {
+            return
+        }
+
+        for edge in 0..self.region.edges().len() {
+            if let Some(time) = self.hit_time(&corner, edge) {
+                self.split_events.push(QueuedEvent {
+                    time,
+                    event: Event::Split { corner: c, edge },
+                });
+            }
+        }
+    }
+
+    // The time the wavefront edge between two corners shrinks to nothing.
+    fn collapse_time(&self, prev: usize, corner: usize) -> Option<f64> {
+        let (start, end) = (self.corners[prev], self.corners[corner]);
+        let now = start.time.max(end.time);
+
+        // The length of the edge changes linearly with time.
+        let dir = self.region.edge_line(end.edges.0).dir().into_inner();
+        let length = dir.dot(&(end.position(now) - start.position(now)));
+        let rate = dir.dot(&(end.velocity? - start.velocity?));
+
+        // Edges of zero length that do not grow are removed right away.
+        if rate < 0.0 {
+            Some(now + length.max(0.0) / -rate)
+        } else if length < EPSILON && rate < EPSILON {
+            Some(now)
+        } else {
+            None
+        }
+    }
+
+    // The time a corner runs into the moving line of an edge, which is strictly after the time
+    // the corner started.
+    fn hit_time(&self, corner: &Corner, edge: usize) -> Option<f64> {
+        let line = self.region.edge_line(edge);
+        let n = line.normal().into_inner();
+
+        // The distance from the corner to the moving line changes linearly with time.
+        let dist = line.offset(corner.time).project_point(corner.point).dist;
+        let rate = n.dot(&corner.velocity?) - 1.0;
+        if rate >= -EPSILON || dist <= 0.0 {
+            return None
+        }
+
+        Some(corner.time + dist / -rate)
+    }
+
+    // Takes the next event from the queues, skipping the events of corners that have left the
+    // wavefront.
+    fn next_event(&mut self) -> Option<QueuedEvent> {
+        let corners = &self.corners;
+        let valid = |e: &QueuedEvent| match e.event {
+            Event::Edge { prev, corner } => corners[prev].alive && corners[corner].alive && corners[corner].prev == prev,
+            Event::Split { corner, edge: _ } => corners[corner].alive,
+        };
+
+        while self.edge_events.peek().is_some_and(|e| !valid(e)) {
+            self.edge_events.pop();
+        }
+        while self.split_events.peek().is_some_and(|e| !valid(e)) {
+            self.split_events.pop();
+        }
+
+        // Edge events go first when they happen at the same time as split events, as they only
+        // change the wavefront locally.
+        match (self.edge_events.peek(), self.split_events.peek()) {
+            (Some(e), Some(s)) if s.time < e.time - EPSILON => self.split_events.pop(),
+            (Some(_), _) => self.edge_events.pop(),
+            (None, _) => self.split_events.pop(),
+        }
+    }
+
+    // Processes the events up to a time.
+    //
+    // Every event removes an edge from the wavefront, except for split events, which replace a
+    // reflex corner by two corners. Corners only run into edges strictly after they start, so
+    // only a finite number of events happen at each time and the time strictly advances.
+    fn advance(&mut self, until: f64) {
+        while let Some(event) = self.next_event() {
+            if event.time > until {
+                break
+            }
+
+            self.time = self.time.max(event.time);
+            match event.event {
+                Event::Edge { prev, corner } => self.edge_event(prev, corner),
+                Event::Split { corner, edge } => self.split_event(corner, edge),
+            }
+        }
+
+        self.time = until;
+    }
+
+    fn edge_event(&mut self, prev: usize, corner: usize) {
+        let now = self.time;
+        let (start, end) = (self.corners[prev], self.corners[corner]);
+
+        let point = na::center(&start.position(now), &end.position(now));
+        let node = self.add_node(point, now);
+        self.finish(prev, node);
+        self.finish(corner, node);
+
+        let c = self.insert(node, (start.edges.0, end.edges.1), Some((start.prev, end.next)));
+        self.cleanup(c);
+    }
+
+    fn split_event(&mut self, c: usize, edge: usize) {
+        let now = self.time;
+        let corner = self.corners[c];
+        let hit = corner.position(now);
+
+        // The wavefront edge on the line that contains the hit at this time, which must not be
+        // one of the edges of the corner. The corner is tested against the lines of all edges of
+        // the region, so there is none if the corner passes the line beside the wavefront edges
+        // on it, or after they have vanished, and then the corner just moves on.
+        let line = self.region.edge_line(edge);
+        let dir = line.dir().into_inner();
+        let target = self.edge_corners[edge].iter()
+            .copied()
+            .filter(|k| self.corners[*k].alive && *k != c && self.corners[*k].prev != c)
+            .find(|k| {
+                let start = self.corners[self.corners[*k].prev].position(now);
+                let end = self.corners[*k].position(now);
+                let length = dir.dot(&(end - start));
+                let pos = dir.dot(&(hit - start));
+                length >= -EPSILON && pos >= -EPSILON && pos <= length + EPSILON
+            });
+        let Some(end) = target else {
+            return
+        };
+        let start = self.corners[end].prev;
+
+        let node = self.add_node(hit, now);
+        self.finish(c, node);
+
+        // The corner splits the edge in two, which splits its loop in two or joins two loops.
+        let (a, b) = corner.edges;
+        let first = self.insert(node, (a, edge), Some((corner.prev, end)));
+        let second = self.insert(node, (edge, b), Some((start, corner.next)));
+        self.cleanup(first);
+        self.cleanup(second);
+    }
+
+    // Removes a collapsed loop or zips up a sliver at a new corner, and schedules the events of
+    // the remaining corner.
+    fn cleanup(&mut self, mut c: usize) {
+        let now = self.time;
+
+        loop {
+            let corner = self.corners[c];
+            if !corner.alive {
+                return
+            }
+
+            if corner.next == c || self.corners[corner.next].next == c {
+                // The loop has collapsed into a point or a line.
+                let mut ends = vec![c];
+                if corner.next != c {
+                    ends.push(corner.next);
+                }
+
+                let nodes: Vec<_> = ends.iter()
+                    .map(|k| {
+                        let node = self.add_node(self.corners[*k].position(now), now);
+                        self.finish(*k, node);
+                        node
+                    })
+                    .collect();
+
+                if let [n1, n2] = nodes[..] {
+                    self.add_arc(n1, n2, corner.edges);
+                }
+                return
+            }
+
+            if corner.velocity.is_some() {
+                self.schedule(c);
+                return
+            }
+
+            // The edges are `p, a, b, q` where `a` and `b` have opposite directions.
+            let (corner_a, corner_b) = (self.corners[corner.prev], self.corners[corner.next]);
+            let (p, (a, b), q) = (corner_a.edges.0, corner.edges, corner_b.edges.1);
+            let point = corner.position(now);
+            let (pa, pb) = (corner_a.position(now), corner_b.position(now));
+            let (da, db) = ((pa - point).norm(), (pb - point).norm());
+
+            // The sliver is zipped up to the closest of the two corners at its ends.
+            c = if (da - db).abs() < EPSILON {
+                let node = self.add_node(na::center(&pa, &pb), now);
+                self.finish(corner.prev, node);
+                self.finish(corner.next, node);
+                self.finish(c, node);
+
+                // A loop of three edges is left with only one edge.
+                let link = (corner_a.prev != corner.next).then_some((corner_a.prev, corner_b.next));
+                self.insert(node, (p, q), link)
+            } else if da < db {
+                let node = self.add_node(pa, now);
+                self.finish(corner.prev, node);
+                self.finish(c, node);
+                self.insert(node, (p, b), Some((corner_a.prev, corner.next)))
+            } else {
+                let node = self.add_node(pb, now);
+                self.finish(corner.next, node);
+                self.finish(c, node);
+                self.insert(node, (a, q), Some((corner.prev, corner_b.next)))
+            };
+        }
+    }
+
+    // The loops of region edges of the wavefront.
+    fn loops(&self) -> Vec<Vec<usize>> {
+        let mut visited = vec![false; self.corners.len()];
+        let mut loops = Vec::new();
+
+        for start in 0..self.corners.len() {
+            if !self.corners[start].alive || visited[start] {
+                continue
+            }
+
+            let mut edges = Vec::new();
+            let mut c = start;
+            while !visited[c] {
+                visited[c] = true;
+                edges.push(self.corners[c].edges.1);
+                c = self.corners[c].next;
+            }
+            loops.push(edges);
+        }
+
+        loops
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::polygons;
+    use crate::offset::JoinType;
+
+    fn find_node(skeleton: &StraightSkeleton, p: (f64, f64)) -> usize {
+        skeleton.nodes.iter()
+            .position(|n| (n.point - na::Point2::new(p.0, p.1)).norm() < 1e-6)
+            .unwrap()
+    }
+
+    #[test]
+    fn skeleton_rectangle() {
+        let region = polygons(&[&[(0.0, 0.0), (0.0, 2.0), (4.0, 2.0), (4.0, 0.0)]]);
+        let skeleton = region.straight_skeleton().unwrap();
+
+        assert_eq!(skeleton.nodes.len(), 6);
+        assert_eq!(skeleton.arcs.len(), 5);
+
+        let (a, b) = (find_node(&skeleton, (1.0, 1.0)), find_node(&skeleton, (3.0, 1.0)));
+        assert!((skeleton.nodes[a].time - 1.0).abs() < 1e-9);
+        assert!((skeleton.nodes[b].time - 1.0).abs() < 1e-9);
+        assert!(skeleton.arcs.iter().any(|arc| arc.nodes == (a, b) || arc.nodes == (b, a)));
+
+        let offset = region.skeleton_offset(0.5).unwrap();
+        assert!((offset.area() - 3.0).abs() < 1e-9);
+        assert!(region.skeleton_offset(1.5).unwrap().is_empty());
+        assert!(region.skeleton_offset(-1.0).is_none());
+    }
+
+    #[test]
+    fn skeleton_reflex_corner() {
+        let region = polygons(&[&[(0.0, 0.0), (0.0, 3.0), (1.0, 3.0), (1.0, 1.0), (3.0, 1.0), (3.0, 0.0)]]);
+        let skeleton = region.straight_skeleton().unwrap();
+
+        // The reflex corner moves along the diagonal to meet the convex corner.
+        let (reflex, convex) = (find_node(&skeleton, (1.0, 1.0)), find_node(&skeleton, (0.5, 0.5)));
+        assert!(skeleton.arcs.iter().any(|arc| arc.nodes == (reflex, convex)));
+        assert!(skeleton.nodes.iter().all(|n| n.time < 0.5 + 1e-9));
+
+        for time in [0.1, 0.25, 0.4] {
+            let offset = region.skeleton_offset(time).unwrap();
+            let miter = region.offset(-time, JoinType::Miter { limit: f64::INFINITY }).unwrap();
+            assert!((offset.area() - miter.area()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn skeleton_split_event() {
+        let region = polygons(&[&[
+            (0.0, 0.0), (0.0, 2.0), (2.0, 2.0), (2.0, 1.2), (4.0, 1.2),
+            (4.0, 2.0), (6.0, 2.0), (6.0, 0.0), (4.0, 0.0), (4.0, 0.8),
+            (2.0, 0.8), (2.0, 0.0),
+        ]]);
+        let skeleton = region.straight_skeleton().unwrap();
+        assert!(skeleton.nodes.iter().any(|n| (n.time - 0.2).abs() < 1e-9));
+
+        let split = region.skeleton_offset(0.3).unwrap();
+        assert_eq!(split.loops().len(), 2);
+        assert!((split.area() - 2.0 * 1.4 * 1.4).abs() < 1e-9);
+        assert!(!split.inside(na::Point2::new(3.0, 1.0)));
+    }
+
+    #[test]
+    fn skeleton_split_miss() {
+        // The reflex corner at (1.2, -0.2) crosses the line of the edge from (-0.6, -0.8) to
+        // (-3.1, -1.4) beside the edge, which is not a split event.
+        let region = polygons(&[&[
+            (1.2, -0.2), (1.5, -3.6), (-0.6, -0.8), (-3.1, -1.4), (-1.9, 1.6), (-0.2, 3.8), (2.6, 2.7),
+        ]]);
+        let skeleton = region.straight_skeleton().unwrap();
+        assert_eq!(skeleton.nodes.len(), 12);
+        assert_eq!(skeleton.arcs.len(), 11);
+
+        let offset = region.skeleton_offset(0.4).unwrap();
+        let miter = region.offset(-0.4, JoinType::Miter { limit: f64::INFINITY }).unwrap();
+        assert_eq!(offset.loops().len(), 1);
+        assert!((offset.area() - miter.area()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn skeleton_hole() {
+        let region = polygons(&[
+            &[(0.0, 0.0), (0.0, 3.0), (3.0, 3.0), (3.0, 0.0)],
+            &[(1.0, 1.0), (2.0, 1.0), (2.0, 2.0), (1.0, 2.0)],
+        ]);
+        let skeleton = region.straight_skeleton().unwrap();
+
+        // The corridors around the hole collapse at the same time along their middle.
+        assert_eq!(skeleton.nodes.len(), 12);
+        assert_eq!(skeleton.arcs.len(), 12);
+        assert!(skeleton.nodes.iter().all(|n| n.time < 0.5 + 1e-9));
+
+        let offset = region.skeleton_offset(0.25).unwrap();
+        assert_eq!(offset.loops().len(), 2);
+        assert!((offset.area() - 4.0).abs() < 1e-9);
+        assert!(region.skeleton_offset(0.6).unwrap().is_empty());
+    }
+
+    #[test]
+    fn skeleton_many_events() {
+        // All edges of a regular polygon vanish at the same time at the center.
+        let n = 64;
+        let points: Vec<_> = (0..n)
+            .map(|i| {
+                let a = -std::f64::consts::TAU * i as f64 / n as f64;
+                (a.cos(), a.sin())
+            })
+            .collect();
+        let region = polygons(&[&points]);
+        let skeleton = region.straight_skeleton().unwrap();
+        assert_eq!(skeleton.nodes.len(), n + 1);
+        assert_eq!(skeleton.arcs.len(), n);
+
+        // A comb whose teeth are cut off by split events where the thin base vanishes.
+        let teeth = 20;
+        let mut points = vec![(0.0, 0.0), (0.0, 3.0)];
+        for i in 0..teeth {
+            let x = 2.0 * i as f64;
+            points.extend([(x + 1.0, 3.0), (x + 1.0, 0.6), (x + 2.0, 0.6), (x + 2.0, 3.0)]);
+        }
+        points.extend([(2.0 * teeth as f64 + 1.0, 3.0), (2.0 * teeth as f64 + 1.0, 0.0)]);
+        let region = polygons(&[&points]);
+        let skeleton = region.straight_skeleton().unwrap();
+        assert!(skeleton.nodes.iter().all(|n| n.time < 0.5 + 1e-9));
+
+        let offset = region.skeleton_offset(0.4).unwrap();
+        let miter = region.offset(-0.4, JoinType::Miter { limit: f64::INFINITY }).unwrap();
+        assert_eq!(offset.loops().len(), teeth + 1);
+        assert!((offset.area() - miter.area()).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,112 @@
+//! Shapes and checks shared by the tests of several modules.
+
+use std::collections::HashMap;
+
+use crate::brep_2d::Region2D;
+use crate::brep_3d::Region3D;
+use crate::geometry::Line;
+use crate::mesh::Mesh;
+
+pub fn polygon(points: &[(f64, f64)]) -> Vec<na::Point2<f64>> {
+    points.iter().map(|(x, y)| na::Point2::new(*x, *y)).collect()
+}
+
+pub fn polygons(polygons: &[&[(f64, f64)]]) -> Region2D {
+    let polygons: Vec<_> = polygons.iter().map(|p| polygon(p)).collect();
+    Region2D::from_polygons(&polygons).unwrap()
+}
+
+/// The corners of a rectangle, clockwise such that the rectangle is on the inside.
+pub fn rect_points(x1: f64, y1: f64, x2: f64, y2: f64) -> Vec<na::Point2<f64>> {
+    polygon(&[(x1, y1), (x1, y2), (x2, y2), (x2, y1)])
+}
+
+pub fn rect(x1: f64, y1: f64, x2: f64, y2: f64) -> Region2D {
+    Region2D::from_polygons(&[rect_points(x1, y1, x2, y2)]).unwrap()
+}
+
+/// The square from `(0, 0)` to `(3, 3)`.
+pub fn square() -> Region2D {
+    rect(0.0, 0.0, 3.0, 3.0)
+}
+
+/// The L shape in the square from `(0, 0)` to `(3, 3)` with arms of width one.
+pub fn l_shape() -> Region2D {
+    polygons(&[&[(0.0, 0.0), (0.0, 3.0), (1.0, 3.0), (1.0, 1.0), (3.0, 1.0), (3.0, 0.0)]])
+}
+
+pub fn line(x1: f64, y1: f64, x2: f64, y2: f64) -> Line {
+    Line::from_two_points(na::Point2::new(x1, y1), na::Point2::new(x2, y2))
+}
+
+/// The vertices and faces of a box, with the faces going counterclockwise seen from the outside,
+/// see [`Region3D::from_polyhedron`].
+pub fn cuboid_polyhedron(min: [f64; 3], max: [f64; 3]) -> (Vec<na::Point3<f64>>, Vec<Vec<Vec<usize>>>) {
+    let vertices = (0..8)
+        .map(|i| na::Point3::new(
+            if i & 1 == 0 { min[0] } else { max[0] },
+            if (i >> 1) & 1 == 0 { min[1] } else { max[1] },
+            if (i >> 2) & 1 == 0 { min[2] } else { max[2] },
+        ))
+        .collect();
+
+    let faces = [
+        [0, 2, 3, 1],
+        [4, 5, 7, 6],
+        [0, 1, 5, 4],
+        [2, 6, 7, 3],
+        [0, 4, 6, 2],
+        [1, 3, 7, 5],
+    ];
+
+    (vertices, faces.iter().map(|f| vec![f.to_vec()]).collect())
+}
+
+pub fn cuboid(min: [f64; 3], max: [f64; 3]) -> Region3D {
+    let (vertices, faces) = cuboid_polyhedron(min, max);
+    Region3D::from_polyhedron(&vertices, &faces).unwrap()
+}
+
+/// A prism of height one over an L shaped profile in the square from `(0, 0)` to `(2, 2)`, which
+/// has a reflex edge from `(1, 1, 0)` to `(1, 1, 1)`.
+pub fn l_prism() -> Region3D {
+    let profile = [(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (1.0, 1.0), (1.0, 2.0), (0.0, 2.0)];
+    let mut vertices = Vec::new();
+    for z in [0.0, 1.0] {
+        vertices.extend(profile.iter().map(|(x, y)| na::Point3::new(*x, *y, z)));
+    }
+
+    let n = profile.len();
+    let mut faces = vec![
+        vec![(0..n).rev().collect()],
+        vec![(n..2 * n).collect()],
+    ];
+    for i in 0..n {
+        let j = (i + 1) % n;
+        faces.push(vec![vec![i, j, j + n, i + n]]);
+    }
+
+    Region3D::from_polyhedron(&vertices, &faces).unwrap()
+}
+
+/// The volume of a mesh, which is only correct for closed meshes with outward facing triangles.
+pub fn mesh_volume(mesh: &Mesh) -> f64 {
+    mesh.faces.iter()
+        .map(|f| {
+            let [a, b, c] = f.map(|i| mesh.vertices[i].cast::<f64>().coords);
+            a.dot(&b.cross(&c)) / 6.0
+        })
+        .sum()
+}
+
+/// Checks that each edge of a mesh is used once in each direction.
+pub fn closed(mesh: &Mesh) -> bool {
+    let mut edges = HashMap::new();
+    for f in &mesh.faces {
+        for i in 0..3 {
+            *edges.entry((f[i], f[(i + 1) % 3])).or_insert(0) += 1;
+        }
+    }
+
+    edges.iter().all(|((a, b), n)| *n == 1 && edges.get(&(*b, *a)) == Some(&1))
+}
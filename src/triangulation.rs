@@ -0,0 +1,733 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::brep_2d::Region2D;
+use crate::mesh::Mesh;
+use crate::predicates::{incircle, orient2d};
+
+/// Parameters for Ruppert refinement of a triangulation.
+#[derive(Copy, Clone, Debug)]
+pub struct RefinementParams {
+    /// Triangles with a smaller angle than this (in radians) are refined.
+    ///
+    /// Refinement is only guaranteed to terminate for angles up to about 20.7 degrees, and when
+    /// the region itself has no smaller angles.
+    pub min_angle: f64,
+    /// Triangles with a larger area than this are refined.
+    pub max_area: Option<f64>,
+    /// Refinement stops after this many points have been added.
+    pub max_steiner_points: usize,
+}
+
+impl Default for RefinementParams {
+    fn default() -> Self {
+        Self {
+            min_angle: 20f64.to_radians(),
+            max_area: None,
+            max_steiner_points: 10000,
+        }
+    }
+}
+
+/// A triangulation of a 2d region.
+///
+/// All triangles are in counterclockwise order.
+#[derive(Clone, Debug)]
+pub struct Triangulation {
+    pub points: Vec<na::Point2<f64>>,
+    pub triangles: Vec<[usize; 3]>,
+}
+
+impl Triangulation {
+    pub fn empty() -> Self {
+        Self {
+            points: Vec::new(),
+            triangles: Vec::new(),
+        }
+    }
+
+    pub fn triangle_points(&self, t: usize) -> [na::Point2<f64>; 3] {
+        self.triangles[t].map(|i| self.points[i])
+    }
+
+    pub fn area(&self) -> f64 {
+        (0..self.triangles.len())
+            .map(|t| triangle_area(self.triangle_points(t)))
+            .sum()
+    }
+
+    /// The smallest angle of any triangle in radians.
+    pub fn min_angle(&self) -> f64 {
+        (0..self.triangles.len())
+            .map(|t| triangle_min_angle(self.triangle_points(t)))
+            .fold(std::f64::consts::PI, f64::min)
+    }
+
+    /// Converts the triangulation to a mesh in the plane `z = 0`.
+    pub fn to_mesh(&self) -> Mesh {
+        self.to_mesh_in_plane(&na::Isometry3::identity())
+    }
+
+    /// Converts the triangulation to a mesh in the `xy` plane of the given frame.
+    pub fn to_mesh_in_plane(&self, frame: &na::Isometry3<f64>) -> Mesh {
+        let vertices = self.points.iter()
+            .map(|p| frame * na::Point3::new(p.x, p.y, 0.0))
+            .map(|p| p.cast::<f32>())
+            .collect();
+
+        Mesh {
+            vertices,
+            faces: self.triangles.clone(),
+        }
+    }
+}
+
+impl Region2D {
+    /// Computes the constrained Delaunay triangulation of the region.
+    pub fn triangulate(&self) -> Triangulation {
+        match Cdt::from_region(self) {
+            Some(cdt) => cdt.triangulation(),
+            None => Triangulation::empty(),
+        }
+    }
+
+    /// Computes a constrained Delaunay triangulation of the region and refines it with Ruppert's
+    /// algorithm until no triangle violates the given limits.
+    pub fn triangulate_refined(&self, params: &RefinementParams) -> Triangulation {
+        match Cdt::from_region(self) {
+            Some(mut cdt) => {
+                cdt.refine(params);
+                cdt.triangulation()
+            }
+            None => Triangulation::empty(),
+        }
+    }
+}
+
+fn triangle_area([a, b, c]: [na::Point2<f64>; 3]) -> f64 {
+    (b - a).perp(&(c - a)) / 2.0
+}
+
+fn triangle_min_angle([a, b, c]: [na::Point2<f64>; 3]) -> f64 {
+    let mut lengths = [(b - c).norm(), (c - a).norm(), (a - b).norm()];
+    lengths.sort_by(f64::total_cmp);
+
+    // The smallest angle is opposite the shortest edge.
+    let sin = 2.0 * triangle_area([a, b, c]).abs() / (lengths[1] * lengths[2]);
+    sin.min(1.0).asin()
+}
+
+fn circumcenter([a, b, c]: [na::Point2<f64>; 3]) -> na::Point2<f64> {
+    let ab = b - a;
+    let ac = c - a;
+    let d = 2.0 * ab.perp(&ac);
+    let x = (ac.y * ab.norm_squared() - ab.y * ac.norm_squared()) / d;
+    let y = (ab.x * ac.norm_squared() - ac.x * ab.norm_squared()) / d;
+    a + na::Vector2::new(x, y)
+}
+
+enum Location {
+    Vertex(usize),
+    Edge(usize, usize),
+    Triangle(usize),
+    Outside,
+}
+
+// The first three vertices of the triangulation form a triangle that contains all other
+// vertices.
+const SUPER_VERTICES: usize = 3;
+
+/// A constrained Delaunay triangulation.
+///
+/// Triangles are stored as vertex triples in counterclockwise order and adjacency is found through
+/// a map from directed edges to the triangle that contains them.
+struct Cdt {
+    points: Vec<na::Point2<f64>>,
+    triangles: Vec<Option<[usize; 3]>>,
+    inside: Vec<bool>,
+    free: Vec<usize>,
+    edges: HashMap<(usize, usize), usize>,
+    // Directed constraint edges, the inside of the region is on the right hand side.
+    constraints: HashSet<(usize, usize)>,
+    vertex_triangle: Vec<usize>,
+    last: usize,
+    // Triangles created since this was last cleared.
+    created: Vec<usize>,
+}
+
+impl Cdt {
+    fn new(min: na::Point2<f64>, max: na::Point2<f64>) -> Self {
+        let center = na::center(&min, &max);
+        let size = (max - min).norm().max(1.0);
+
+        let mut cdt = Self {
+            points: vec![
+                center + na::Vector2::new(-20.0, -10.0) * size,
+                center + na::Vector2::new(20.0, -10.0) * size,
+                center + na::Vector2::new(0.0, 20.0) * size,
+            ],
+            triangles: Vec::new(),
+            inside: Vec::new(),
+            free: Vec::new(),
+            edges: HashMap::new(),
+            constraints: HashSet::new(),
+            vertex_triangle: vec![0; SUPER_VERTICES],
+            last: 0,
+            created: Vec::new(),
+        };
+
+        cdt.add_triangle([0, 1, 2], false);
+        cdt
+    }
+
+    fn from_region(region: &Region2D) -> Option<Self> {
+        let loops = region.loops();
+        let vertices = region.loop_vertices();
+
+        let mut points = vertices.iter().flatten();
+        let first = *points.next()?;
+        let (min, max) = points.fold((first, first), |(min, max), p| {
+            (min.coords.inf(&p.coords).into(), max.coords.sup(&p.coords).into())
+        });
+
+        let mut cdt = Self::new(min, max);
+
+        let ids: Vec<Vec<usize>> = vertices.iter()
+            .map(|vs| vs.iter().map(|p| cdt.insert_point(*p).unwrap()).collect())
+            .collect();
+
+        for (edge_loop, ids) in loops.iter().zip(&ids) {
+            for i in 0..edge_loop.len() {
+                let prev = ids[(i + edge_loop.len() - 1) % edge_loop.len()];
+                cdt.insert_constraint(prev, ids[i]);
+            }
+        }
+
+        cdt.classify();
+        Some(cdt)
+    }
+
+    fn triangulation(&self) -> Triangulation {
+        let mut index = vec![None; self.points.len()];
+        let mut points = Vec::new();
+        let mut triangles = Vec::new();
+
+        for (t, v) in self.triangles.iter().enumerate() {
+            if let Some(v) = v.filter(|_| self.inside[t]) {
+                triangles.push(v.map(|i| *index[i].get_or_insert_with(|| {
+                    points.push(self.points[i]);
+                    points.len() - 1
+                })));
+            }
+        }
+
+        Triangulation {
+            points,
+            triangles,
+        }
+    }
+
+    fn add_triangle(&mut self, v: [usize; 3], inside: bool) -> usize {
+        let t = match self.free.pop() {
+            Some(t) => {
+                self.triangles[t] = Some(v);
+                self.inside[t] = inside;
+                t
+            }
+            None => {
+                self.triangles.push(Some(v));
+                self.inside.push(inside);
+                self.triangles.len() - 1
+            }
+        };
+
+        for i in 0..3 {
+            self.edges.insert((v[i], v[(i + 1) % 3]), t);
+            self.vertex_triangle[v[i]] = t;
+        }
+
+        self.created.push(t);
+        self.last = t;
+        t
+    }
+
+    fn remove_triangle(&mut self, t: usize) -> [usize; 3] {
+        let v = self.triangles[t].take().unwrap();
+        for i in 0..3 {
+            self.edges.remove(&(v[i], v[(i + 1) % 3]));
+        }
+        self.free.push(t);
+        v
+    }
+
+    fn add_point(&mut self, p: na::Point2<f64>) -> usize {
+        self.points.push(p);
+        self.vertex_triangle.push(0);
+        self.points.len() - 1
+    }
+
+    // The triangle containing the directed edge together with the vertex opposite to the edge.
+    fn edge_triangle(&self, a: usize, b: usize) -> Option<(usize, usize)> {
+        let t = *self.edges.get(&(a, b))?;
+        let v = self.triangles[t].unwrap();
+        let c = v.into_iter().find(|i| *i != a && *i != b).unwrap();
+        Some((t, c))
+    }
+
+    fn is_constrained(&self, a: usize, b: usize) -> bool {
+        self.constraints.contains(&(a, b)) || self.constraints.contains(&(b, a))
+    }
+
+    fn vertex_triangle(&self, a: usize) -> usize {
+        let t = self.vertex_triangle[a];
+        if self.triangles[t].is_some_and(|v| v.contains(&a)) {
+            t
+        } else {
+            self.triangles.iter()
+                .position(|v| v.is_some_and(|v| v.contains(&a)))
+                .unwrap()
+        }
+    }
+
+    fn locate(&self, p: na::Point2<f64>, start: usize) -> Location {
+        let mut t = if self.triangles[start].is_some() {
+            start
+        } else {
+            self.triangles.iter().position(|v| v.is_some()).unwrap()
+        };
+
+        // A visibility walk is not guaranteed to terminate in a non-Delaunay triangulation, so we
+        // vary the first edge we try and fall back to a linear search.
+        'walk: for step in 0..self.triangles.len() + 3 {
+            let v = self.triangles[t].unwrap();
+            for k in 0..3 {
+                let i = (k + step) % 3;
+                let (a, b) = (v[i], v[(i + 1) % 3]);
+                if orient2d(self.points[a], self.points[b], p) < 0.0 {
+                    match self.edges.get(&(b, a)) {
+                        Some(n) => {
+                            t = *n;
+                            continue 'walk
+                        }
+                        None => return Location::Outside,
+                    }
+                }
+            }
+
+            return self.locate_in_triangle(t, p)
+        }
+
+        for (t, v) in self.triangles.iter().enumerate() {
+            if let Some(v) = v {
+                if (0..3).all(|i| orient2d(self.points[v[i]], self.points[v[(i + 1) % 3]], p) >= 0.0) {
+                    return self.locate_in_triangle(t, p)
+                }
+            }
+        }
+
+        Location::Outside
+    }
+
+    fn locate_in_triangle(&self, t: usize, p: na::Point2<f64>) -> Location {
+        let v = self.triangles[t].unwrap();
+
+        if let Some(a) = v.into_iter().find(|a| self.points[*a] == p) {
+            return Location::Vertex(a)
+        }
+
+        for i in 0..3 {
+            let (a, b) = (v[i], v[(i + 1) % 3]);
+            if orient2d(self.points[a], self.points[b], p) == 0.0 {
+                return Location::Edge(a, b)
+            }
+        }
+
+        Location::Triangle(t)
+    }
+
+    fn insert_point(&mut self, p: na::Point2<f64>) -> Option<usize> {
+        self.insert_point_from(p, self.last)
+    }
+
+    fn insert_point_from(&mut self, p: na::Point2<f64>, start: usize) -> Option<usize> {
+        match self.locate(p, start) {
+            Location::Vertex(a) => Some(a),
+            Location::Edge(a, b) => Some(self.split_edge(a, b, p)),
+            Location::Triangle(t) => {
+                let inside = self.inside[t];
+                let v = self.remove_triangle(t);
+                let i = self.add_point(p);
+
+                for k in 0..3 {
+                    self.add_triangle([v[k], v[(k + 1) % 3], i], inside);
+                }
+
+                self.restore_delaunay(vec![(v[0], v[1]), (v[1], v[2]), (v[2], v[0])]);
+                Some(i)
+            }
+            Location::Outside => None,
+        }
+    }
+
+    // Splits the edge between `a` and `b` by inserting a new vertex at `p`. The point is assumed
+    // to be (close to) the edge.
+    fn split_edge(&mut self, a: usize, b: usize, p: na::Point2<f64>) -> usize {
+        let i = self.add_point(p);
+        let mut stack = Vec::new();
+
+        if let Some((t, c)) = self.edge_triangle(a, b) {
+            let inside = self.inside[t];
+            self.remove_triangle(t);
+            self.add_triangle([a, i, c], inside);
+            self.add_triangle([i, b, c], inside);
+            stack.extend([(b, c), (c, a)]);
+        }
+
+        if let Some((t, d)) = self.edge_triangle(b, a) {
+            let inside = self.inside[t];
+            self.remove_triangle(t);
+            self.add_triangle([b, i, d], inside);
+            self.add_triangle([i, a, d], inside);
+            stack.extend([(a, d), (d, b)]);
+        }
+
+        if self.constraints.remove(&(a, b)) {
+            self.constraints.insert((a, i));
+            self.constraints.insert((i, b));
+        } else if self.constraints.remove(&(b, a)) {
+            self.constraints.insert((b, i));
+            self.constraints.insert((i, a));
+        }
+
+        self.restore_delaunay(stack);
+        i
+    }
+
+    // Flips the edge between the two triangles `(a, b, p)` and `(b, a, q)` to `(p, q)`.
+    fn flip(&mut self, a: usize, b: usize) {
+        let (t1, p) = self.edge_triangle(a, b).unwrap();
+        let (t2, q) = self.edge_triangle(b, a).unwrap();
+        let inside = self.inside[t1];
+
+        self.remove_triangle(t1);
+        self.remove_triangle(t2);
+        self.add_triangle([a, q, p], inside);
+        self.add_triangle([q, b, p], inside);
+    }
+
+    // Flips edges until all unconstrained edges reachable from the given edges are Delaunay.
+    fn restore_delaunay(&mut self, mut stack: Vec<(usize, usize)>) {
+        while let Some((a, b)) = stack.pop() {
+            if self.is_constrained(a, b) {
+                continue
+            }
+
+            let (Some((_, p)), Some((_, q))) = (self.edge_triangle(a, b), self.edge_triangle(b, a)) else {
+                continue
+            };
+
+            if incircle(self.points[a], self.points[b], self.points[p], self.points[q]) > 0.0 {
+                self.flip(a, b);
+                stack.extend([(a, q), (q, b), (b, p), (p, a)]);
+            }
+        }
+    }
+
+    fn insert_constraint(&mut self, a: usize, b: usize) {
+        if a == b {
+            return
+        }
+
+        if self.edges.contains_key(&(a, b)) || self.edges.contains_key(&(b, a)) {
+            self.constraints.insert((a, b));
+            return
+        }
+
+        let crossing = match self.crossing_edges(a, b) {
+            Ok(crossing) => crossing,
+            Err(c) => {
+                // The constraint goes through another vertex, so we split it in two.
+                self.insert_constraint(a, c);
+                self.insert_constraint(c, b);
+                return
+            }
+        };
+
+        let pa = self.points[a];
+        let pb = self.points[b];
+
+        let mut queue = VecDeque::from(crossing);
+        let mut new_edges = Vec::new();
+
+        // Remove the crossing edges by flipping them, see Sloan, "A fast algorithm for generating
+        // constrained Delaunay triangulations".
+        while let Some((u, v)) = queue.pop_front() {
+            let (_, p) = self.edge_triangle(u, v).unwrap();
+            let (_, q) = self.edge_triangle(v, u).unwrap();
+
+            let ou = orient2d(self.points[p], self.points[q], self.points[u]);
+            let ov = orient2d(self.points[p], self.points[q], self.points[v]);
+
+            if ou == 0.0 || ov == 0.0 || (ou > 0.0) == (ov > 0.0) {
+                // The quad is not strictly convex, try again later.
+                queue.push_back((u, v));
+                continue
+            }
+
+            self.flip(u, v);
+
+            let crosses = p != a && p != b && q != a && q != b
+                && (orient2d(pa, pb, self.points[p]) > 0.0) != (orient2d(pa, pb, self.points[q]) > 0.0);
+
+            if crosses {
+                queue.push_back((p, q));
+            } else {
+                new_edges.push((p, q));
+            }
+        }
+
+        self.constraints.insert((a, b));
+        self.restore_delaunay(new_edges);
+    }
+
+    // Finds the edges that cross the segment from `a` to `b`, or a vertex that lies on it.
+    //
+    // The edges are returned such that the first vertex is to the right of the segment.
+    fn crossing_edges(&self, a: usize, b: usize) -> Result<Vec<(usize, usize)>, usize> {
+        let pa = self.points[a];
+        let pb = self.points[b];
+
+        let on_segment = |x: usize| {
+            orient2d(pa, pb, self.points[x]) == 0.0 && (self.points[x] - pa).dot(&(pb - pa)) > 0.0
+        };
+
+        // Rotate around `a` until we find the triangle containing the start of the segment.
+        let mut t = self.vertex_triangle(a);
+        let (mut r, mut l) = loop {
+            let v = self.triangles[t].unwrap();
+            let i = v.iter().position(|i| *i == a).unwrap();
+            let (x, y) = (v[(i + 1) % 3], v[(i + 2) % 3]);
+
+            if on_segment(x) {
+                return Err(x)
+            }
+
+            if orient2d(pa, pb, self.points[x]) < 0.0 && orient2d(pa, pb, self.points[y]) > 0.0 {
+                break (x, y)
+            }
+
+            t = self.edges[&(a, y)];
+        };
+
+        let mut crossing = vec![(r, l)];
+
+        loop {
+            let (_, z) = self.edge_triangle(l, r).unwrap();
+            if z == b {
+                return Ok(crossing)
+            }
+
+            let o = orient2d(pa, pb, self.points[z]);
+            if o == 0.0 {
+                return Err(z)
+            } else if o > 0.0 {
+                l = z;
+            } else {
+                r = z;
+            }
+
+            crossing.push((r, l));
+        }
+    }
+
+    // Marks all triangles inside the region by flooding from the inside of each constraint.
+    fn classify(&mut self) {
+        self.inside.iter_mut().for_each(|x| *x = false);
+
+        let mut stack: Vec<_> = self.constraints.iter()
+            .filter_map(|(a, b)| self.edges.get(&(*b, *a)).copied())
+            .collect();
+
+        while let Some(t) = stack.pop() {
+            if self.inside[t] {
+                continue
+            }
+            self.inside[t] = true;
+
+            let v = self.triangles[t].unwrap();
+            for i in 0..3 {
+                let (a, b) = (v[i], v[(i + 1) % 3]);
+                if !self.is_constrained(a, b) {
+                    stack.extend(self.edges.get(&(b, a)));
+                }
+            }
+        }
+    }
+
+    fn is_bad(&self, v: [usize; 3], params: &RefinementParams) -> bool {
+        let points = v.map(|i| self.points[i]);
+        triangle_min_angle(points) < params.min_angle
+            || params.max_area.is_some_and(|max| triangle_area(points) > max)
+    }
+
+    // Checks if `p` is strictly inside the diametral circle of a segment.
+    fn encroaches(&self, (a, b): (usize, usize), p: na::Point2<f64>) -> bool {
+        (self.points[a] - p).dot(&(self.points[b] - p)) < 0.0
+    }
+
+    // Checks if a segment is encroached by a vertex on the inside of the region.
+    fn segment_encroached(&self, (a, b): (usize, usize)) -> bool {
+        [(a, b), (b, a)].into_iter()
+            .filter_map(|(a, b)| self.edge_triangle(a, b))
+            .any(|(t, c)| self.inside[t] && self.encroaches((a, b), self.points[c]))
+    }
+
+    fn split_segment(&mut self, (a, b): (usize, usize)) {
+        self.split_edge(a, b, na::center(&self.points[a], &self.points[b]));
+    }
+
+    // Refines the triangulation with Ruppert's algorithm, see Shewchuk, "Delaunay refinement
+    // algorithms for triangular mesh generation".
+    fn refine(&mut self, params: &RefinementParams) {
+        let mut segments: Vec<_> = self.constraints.iter().copied().collect();
+        let mut triangles: Vec<_> = (0..self.triangles.len()).collect();
+        let mut steiner_points = 0;
+
+        self.created.clear();
+
+        while steiner_points < params.max_steiner_points {
+            if let Some(s) = segments.pop() {
+                if self.constraints.contains(&s) && self.segment_encroached(s) {
+                    self.split_segment(s);
+                    steiner_points += 1;
+                }
+            } else if let Some(t) = triangles.pop() {
+                let Some(v) = self.triangles[t].filter(|v| self.inside[t] && self.is_bad(*v, params)) else {
+                    continue
+                };
+
+                let c = circumcenter(v.map(|i| self.points[i]));
+                let encroached: Vec<_> = self.constraints.iter()
+                    .copied()
+                    .filter(|s| self.encroaches(*s, c))
+                    .collect();
+
+                if encroached.is_empty() {
+                    let inside = match self.locate(c, t) {
+                        Location::Triangle(t2) => self.inside[t2],
+                        Location::Edge(a, b) => !self.is_constrained(a, b) && self.inside[self.edges[&(a, b)]],
+                        _ => false,
+                    };
+
+                    // Otherwise the circumcenter is outside of the region, which can only happen
+                    // due to rounding. We leave the triangle as is.
+                    if inside {
+                        self.insert_point_from(c, t);
+                        steiner_points += 1;
+                    }
+                } else {
+                    for s in encroached.into_iter().take(params.max_steiner_points - steiner_points) {
+                        self.split_segment(s);
+                        steiner_points += 1;
+                    }
+                    triangles.push(t);
+                }
+            } else {
+                break
+            }
+
+            for t in self.created.drain(..) {
+                let Some(v) = self.triangles[t] else {
+                    continue
+                };
+
+                if self.inside[t] {
+                    triangles.push(t);
+                }
+
+                for i in 0..3 {
+                    let (a, b) = (v[i], v[(i + 1) % 3]);
+                    if self.constraints.contains(&(a, b)) {
+                        segments.push((a, b));
+                    } else if self.constraints.contains(&(b, a)) {
+                        segments.push((b, a));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::polygons;
+
+    fn square_with_hole() -> Region2D {
+        polygons(&[
+            &[(0.0, 0.0), (0.0, 3.0), (3.0, 3.0), (3.0, 0.0)],
+            &[(1.0, 1.0), (2.0, 1.0), (2.0, 2.0), (1.0, 2.0)],
+        ])
+    }
+
+    fn check_triangulation(region: &Region2D, triangulation: &Triangulation) {
+        for t in 0..triangulation.triangles.len() {
+            let [a, b, c] = triangulation.triangle_points(t);
+            assert!(orient2d(a, b, c) > 0.0);
+            assert!(region.inside(na::center(&na::center(&a, &b), &c)));
+        }
+    }
+
+    #[test]
+    fn triangulate_region_with_hole() {
+        let region = square_with_hole();
+        let triangulation = region.triangulate();
+
+        check_triangulation(&region, &triangulation);
+        assert_eq!(triangulation.points.len(), 8);
+        assert_eq!(triangulation.triangles.len(), 8);
+        assert!((triangulation.area() - 8.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn triangulate_concave_region() {
+        let region = polygons(&[
+            &[(0.0, 0.0), (0.0, 3.0), (1.0, 3.0), (1.0, 1.0), (2.0, 1.0), (2.0, 3.0), (3.0, 3.0), (3.0, 0.0)],
+        ]);
+        let triangulation = region.triangulate();
+
+        check_triangulation(&region, &triangulation);
+        assert_eq!(triangulation.triangles.len(), 6);
+        assert!((triangulation.area() - 7.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn triangulate_refined_quality() {
+        let region = polygons(&[
+            &[(0.0, 0.0), (0.0, 1.0), (10.0, 1.2), (10.0, 0.0)],
+            &[(4.0, 0.4), (6.0, 0.4), (5.0, 0.6)],
+        ]);
+
+        let params = RefinementParams {
+            min_angle: 25f64.to_radians(),
+            max_area: Some(0.1),
+            ..Default::default()
+        };
+
+        let triangulation = region.triangulate_refined(&params);
+
+        check_triangulation(&region, &triangulation);
+        assert!(triangulation.min_angle() >= params.min_angle);
+        assert!(triangulation.triangles.iter().enumerate()
+            .all(|(t, _)| triangle_area(triangulation.triangle_points(t)) <= 0.1));
+        assert!((triangulation.area() - (11.0 - 0.2)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn triangulation_to_mesh() {
+        let frame = na::Isometry3::translation(0.0, 0.0, 2.0);
+        let mesh = square_with_hole().triangulate().to_mesh_in_plane(&frame);
+
+        assert!((mesh.area() - 8.0).abs() < 1e-5);
+        assert!(mesh.vertices.iter().all(|v| v.z == 2.0));
+    }
+}
@@ -0,0 +1,556 @@
+//! Resolution of loops of lines into regions by their winding number.
+//!
+//! The loops may intersect themselves and each other. All intersections are computed, the loops
+//! are split into sub-edges at the intersections and the winding number on each side of each
+//! sub-edge is computed. The sub-edges that separate a filled side from a non filled side form the
+//! boundary of the resulting region.
+
+use std::collections::HashMap;
+
+use crate::brep_2d::Region2D;
+use crate::geometry::{Line, LineSegment, EPSILON};
+
+// A part of a loop edge, between two intersections with other edges.
+struct SubEdge {
+    // Nodes with `a < b`.
+    a: usize,
+    b: usize,
+    line: usize,
+    // Whether walking from `a` to `b` follows the direction of the line.
+    forward: bool,
+    // The number of times the loops pass from `a` to `b`, minus the number of times they pass from
+    // `b` to `a`.
+    winding: i32,
+}
+
+// A sub-edge on the boundary of the resulting region, the inside is on the right hand side.
+struct BoundaryEdge {
+    from: usize,
+    to: usize,
+    line: usize,
+    flip: bool,
+}
+
+/// How the winding number of a point decides if the point is filled.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FillRule {
+    /// Points with a non-zero winding number are filled.
+    NonZero,
+    /// Points with an odd winding number are filled.
+    EvenOdd,
+}
+
+impl FillRule {
+    pub fn filled(self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+impl Region2D {
+    /// Creates a region from loops of lines that may intersect themselves and each other.
+    ///
+    /// Each loop is a list of line indices, where the vertices of the loop are the intersections
+    /// of consecutive lines. The loops are split at their intersections and the parts that are
+    /// filled according to the fill rule become the region. Returns `None` if consecutive lines
+    /// of a loop do not intersect, or if the filled parts can not be represented as a region, for
+    /// example if they only touch at a vertex.
+    pub fn resolve_loops(lines: &[Line], loops: &[Vec<usize>], rule: FillRule) -> Option<Self> {
+        resolve(lines, loops, |w| rule.filled(w))
+    }
+
+    /// Creates a region from polygons that may intersect themselves and each other, see
+    /// [`Self::resolve_loops`].
+    ///
+    /// Repeated points and polygons going back on themselves are allowed.
+    pub fn resolve_polygons(polygons: &[Vec<na::Point2<f64>>], rule: FillRule) -> Option<Self> {
+        resolve_polygons(polygons, |w| rule.filled(w))
+    }
+}
+
+// Resolves polygons into a region containing all points where the winding number is filled, see
+// `resolve`.
+pub(crate) fn resolve_polygons<F: Fn(i32) -> bool>(polygons: &[Vec<na::Point2<f64>>], filled: F) -> Option<Region2D> {
+    let mut lines = Vec::new();
+    let mut segments = Vec::new();
+
+    for polygon in polygons {
+        let n = polygon.len();
+        for i in 0..n {
+            let (p1, p2) = (polygon[i], polygon[(i + 1) % n]);
+            if (p2 - p1).norm() <= EPSILON {
+                continue
+            }
+
+            let line = Line::from_two_points(p1, p2);
+            let start = line.project_point(p1).pos;
+            let end = line.project_point(p2).pos;
+
+            segments.push(Segment {
+                line: lines.len(),
+                start,
+                end,
+                splits: vec![(start, p1), (end, p2)],
+            });
+            lines.push(line);
+        }
+    }
+
+    Some(resolve_segments(&lines, segments, filled)?.0)
+}
+
+// Resolves loops of lines into a region containing all points where the winding number is
+// filled.
+//
+// The winding number is the number of times the loops go clockwise around a point, so a valid
+// region has winding number one on its inside.
+pub(crate) fn resolve<F: Fn(i32) -> bool>(lines: &[Line], loops: &[Vec<usize>], filled: F) -> Option<Region2D> {
+    Some(resolve_sourced(lines, loops, filled)?.0)
+}
+
+// Resolves loops of lines into a region, see `resolve`, and returns the index of the line each
+// edge of the region lies on. Edges that merge several parts on coincident lines get the line of
+// the first part in the order of the loop.
+pub(crate) fn resolve_sourced<F: Fn(i32) -> bool>(
+    lines: &[Line],
+    loops: &[Vec<usize>],
+    filled: F,
+) -> Option<(Region2D, Vec<usize>)> {
+    let mut segments = Vec::new();
+
+    for l in loops {
+        let mut l = l.clone();
+        l.dedup_by(|a, b| lines[*a].coincident(&lines[*b]));
+        while l.len() > 1 && lines[l[0]].coincident(&lines[l[l.len() - 1]]) {
+            l.pop();
+        }
+
+        if l.len() < 3 {
+            continue
+        }
+
+        let n = l.len();
+        for i in 0..n {
+            let line = lines[l[i]];
+            let prev = lines[l[(i + n - 1) % n]];
+            let next = lines[l[(i + 1) % n]];
+
+            let start = line.intersection(&prev)?.pos;
+            let end = line.intersection(&next)?.pos;
+
+            if start != end {
+                segments.push(Segment {
+                    line: l[i],
+                    start,
+                    end,
+                    splits: vec![
+                        (start, prev.intersection_point(&line)?),
+                        (end, line.intersection_point(&next)?),
+                    ],
+                });
+            }
+        }
+    }
+
+    resolve_segments(lines, segments, filled)
+}
+
+fn resolve_segments<F: Fn(i32) -> bool>(
+    lines: &[Line],
+    mut segments: Vec<Segment>,
+    filled: F,
+) -> Option<(Region2D, Vec<usize>)> {
+    split_segments(lines, &mut segments);
+
+    let (nodes, node_ids) = merge_nodes(&segments);
+
+    let mut edges: Vec<SubEdge> = Vec::new();
+    let mut edge_map = HashMap::new();
+
+    for (segment, ids) in segments.iter_mut().zip(&node_ids) {
+        let forward = segment.end > segment.start;
+        let mut splits: Vec<_> = segment.splits.iter()
+            .map(|(pos, _)| *pos)
+            .zip(ids.iter().copied())
+            .collect();
+
+        if forward {
+            splits.sort_by(|x, y| x.0.total_cmp(&y.0));
+        } else {
+            splits.sort_by(|x, y| y.0.total_cmp(&x.0));
+        }
+
+        splits.dedup_by_key(|x| x.1);
+
+        for w in splits.windows(2) {
+            let (u, v) = (w[0].1, w[1].1);
+            let (a, b) = (u.min(v), u.max(v));
+            let (edge_forward, winding) = if u == a {
+                (forward, 1)
+            } else {
+                (!forward, -1)
+            };
+
+            let e = *edge_map.entry((a, b)).or_insert_with(|| {
+                edges.push(SubEdge {
+                    a,
+                    b,
+                    line: segment.line,
+                    forward: edge_forward,
+                    winding: 0,
+                });
+                edges.len() - 1
+            });
+
+            edges[e].winding += winding;
+        }
+    }
+
+    edges.retain(|e| e.winding != 0);
+
+    let segments: Vec<_> = edges.iter()
+        .map(|e| LineSegment::new(nodes[e.a], nodes[e.b]))
+        .collect();
+
+    let mut boundary = Vec::new();
+
+    for (i, e) in edges.iter().enumerate() {
+        let pa = nodes[e.a];
+        let pb = nodes[e.b];
+        let d = pb - pa;
+
+        // Cast a ray from the middle of the edge towards its right hand side.
+        let mid = na::center(&pa, &pb);
+        let ray = Line::from_point_dir(mid, na::Vector2::new(d.y, -d.x));
+        let start = ray.project_point(mid).pos;
+
+        let crossings: i32 = edges.iter().zip(&segments).enumerate()
+            .filter(|(j, _)| *j != i)
+            .filter_map(|(_, (f, s))| Some((f, ray.segment_intersection(s)?)))
+            .filter(|(_, x)| x.pos > start)
+            .map(|(f, x)| if x.dir { f.winding } else { -f.winding })
+            .sum();
+
+        let right = -crossings;
+        let left = right - e.winding;
+
+        if filled(right) && !filled(left) {
+            boundary.push(BoundaryEdge {
+                from: e.a,
+                to: e.b,
+                line: e.line,
+                flip: !e.forward,
+            });
+        } else if filled(left) && !filled(right) {
+            boundary.push(BoundaryEdge {
+                from: e.b,
+                to: e.a,
+                line: e.line,
+                flip: e.forward,
+            });
+        }
+    }
+
+    let links: Vec<_> = boundary.iter().map(|e| (e.from, e.to)).collect();
+    let boundary_loops = link_boundary(&nodes, &links)?;
+
+    let mut region_lines = Vec::new();
+    let mut region_sources = Vec::new();
+    let mut region_loops = Vec::new();
+
+    for boundary_loop in boundary_loops {
+        let mut keys: Vec<(usize, bool)> = Vec::new();
+
+        for e in boundary_loop.iter().map(|i| &boundary[*i]) {
+            let key = (e.line, e.flip);
+            if keys.last().is_some_and(|last| mergeable(lines, *last, key)) {
+                continue
+            }
+            keys.push(key);
+        }
+
+        while keys.len() > 1 && mergeable(lines, keys[0], keys[keys.len() - 1]) {
+            keys.pop();
+        }
+
+        if keys.len() < 3 {
+            continue
+        }
+
+        let s = region_lines.len();
+        region_loops.push((s..s + keys.len()).collect());
+        region_sources.extend(keys.iter().map(|(line, _)| *line));
+        region_lines.extend(keys.into_iter().map(|(line, flip)| {
+            if flip {
+                lines[line].flip()
+            } else {
+                lines[line]
+            }
+        }));
+    }
+
+    // Each loop entry has its own line, so the line of an edge gives its source.
+    let region = Region2D::from_loops(region_lines, &region_loops)?;
+    let sources: Vec<_> = region.edges().iter().map(|e| region_sources[e.line]).collect();
+    let (region, merged) = region.simplify_sourced()?;
+
+    Some((region, merged.into_iter().map(|e| sources[e]).collect()))
+}
+
+// Consecutive boundary edges on the same line, or on parallel lines which must then be the same
+// line, are merged to a single edge.
+fn mergeable(lines: &[Line], (l1, f1): (usize, bool), (l2, f2): (usize, bool)) -> bool {
+    (l1 == l2 && f1 == f2) || lines[l1].intersection(&lines[l2]).is_none()
+}
+
+struct Segment {
+    line: usize,
+    start: f64,
+    end: f64,
+    // Positions along the line where the segment is split, together with the split point.
+    splits: Vec<(f64, na::Point2<f64>)>,
+}
+
+impl Segment {
+    fn contains(&self, pos: f64) -> bool {
+        pos >= self.start.min(self.end) - EPSILON && pos <= self.start.max(self.end) + EPSILON
+    }
+
+    fn clamp(&self, pos: f64) -> f64 {
+        pos.clamp(self.start.min(self.end), self.start.max(self.end))
+    }
+}
+
+fn split_segments(lines: &[Line], segments: &mut [Segment]) {
+    for i in 0..segments.len() {
+        for j in i + 1..segments.len() {
+            let li = lines[segments[i].line];
+            let lj = lines[segments[j].line];
+
+            match (li.intersection(&lj), lj.intersection(&li)) {
+                (Some(xi), Some(xj)) => {
+                    if segments[i].contains(xi.pos) && segments[j].contains(xj.pos) {
+                        let p = li.intersection_point(&lj).unwrap();
+                        let pos_i = segments[i].clamp(xi.pos);
+                        let pos_j = segments[j].clamp(xj.pos);
+                        segments[i].splits.push((pos_i, p));
+                        segments[j].splits.push((pos_j, p));
+                    }
+                }
+                _ => {
+                    // Parallel segments only intersect if they overlap on the same line, in which
+                    // case they are split at each others endpoints.
+                    if li.project_point(lj.origin()).dist.abs() > EPSILON {
+                        continue
+                    }
+
+                    for (a, b, la) in [(i, j, li), (j, i, lj)] {
+                        for k in 0..2 {
+                            let p = segments[b].splits[k].1;
+                            let pos = la.project_point(p).pos;
+                            if segments[a].contains(pos) {
+                                let pos = segments[a].clamp(pos);
+                                segments[a].splits.push((pos, p));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Merges split points that are closer than `EPSILON` into nodes.
+//
+// Returns the position of each node and the node of each split of each segment.
+fn merge_nodes(segments: &[Segment]) -> (Vec<na::Point2<f64>>, Vec<Vec<usize>>) {
+    let points: Vec<_> = segments.iter()
+        .flat_map(|s| s.splits.iter().map(|x| x.1))
+        .collect();
+
+    let (nodes, mut node_ids) = merge_points(&points);
+
+    let mut ids = Vec::new();
+    for s in segments.iter().rev() {
+        ids.push(node_ids.split_off(node_ids.len() - s.splits.len()));
+    }
+    ids.reverse();
+
+    (nodes, ids)
+}
+
+/// Merges points that are closer than `EPSILON` to each other.
+///
+/// Returns the merged points and the index of the merged point of each point.
+pub(crate) fn merge_points(points: &[na::Point2<f64>]) -> (Vec<na::Point2<f64>>, Vec<usize>) {
+    let mut parent: Vec<_> = (0..points.len()).collect();
+
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    let mut order: Vec<_> = (0..points.len()).collect();
+    order.sort_by(|a, b| points[*a].x.total_cmp(&points[*b].x));
+
+    for (k, &i) in order.iter().enumerate() {
+        for &j in &order[k + 1..] {
+            if points[j].x - points[i].x > EPSILON {
+                break
+            }
+
+            if (points[j] - points[i]).norm() <= EPSILON {
+                let (ri, rj) = (find(&mut parent, i), find(&mut parent, j));
+                parent[ri.max(rj)] = ri.min(rj);
+            }
+        }
+    }
+
+    let mut index = HashMap::new();
+    let mut merged = Vec::new();
+
+    let ids = (0..points.len())
+        .map(|i| {
+            let root = find(&mut parent, i);
+            *index.entry(root).or_insert_with(|| {
+                merged.push(points[root]);
+                merged.len() - 1
+            })
+        })
+        .collect();
+
+    (merged, ids)
+}
+
+// Links boundary edges, given as pairs of nodes, into loops.
+//
+// When several boundary edges meet at a node the edges are paired such that the loops do not
+// cross each other.
+pub(crate) fn link_boundary(nodes: &[na::Point2<f64>], boundary: &[(usize, usize)]) -> Option<Vec<Vec<usize>>> {
+    let mut outgoing: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (i, e) in boundary.iter().enumerate() {
+        outgoing.entry(e.0).or_default().push(i);
+    }
+
+    let angle = |from: usize, to: usize| {
+        let d = nodes[to] - nodes[from];
+        d.y.atan2(d.x)
+    };
+
+    let mut next = vec![None; boundary.len()];
+    let mut used = vec![false; boundary.len()];
+
+    for (i, e) in boundary.iter().enumerate() {
+        // Pick the first outgoing edge counterclockwise from the edge we came from.
+        let back = angle(e.1, e.0);
+        let candidates = outgoing.get(&e.1)?;
+        let n = candidates.iter()
+            .copied()
+            .filter(|j| !used[*j])
+            .min_by(|a, b| {
+                let da = (angle(e.1, boundary[*a].1) - back).rem_euclid(std::f64::consts::TAU);
+                let db = (angle(e.1, boundary[*b].1) - back).rem_euclid(std::f64::consts::TAU);
+                da.total_cmp(&db)
+            })?;
+
+        used[n] = true;
+        next[i] = Some(n);
+    }
+
+    let mut visited = vec![false; boundary.len()];
+    let mut loops = Vec::new();
+
+    for start in 0..boundary.len() {
+        let mut boundary_loop = Vec::new();
+        let mut e = start;
+        while !visited[e] {
+            visited[e] = true;
+            boundary_loop.push(e);
+            e = next[e]?;
+        }
+
+        if !boundary_loop.is_empty() {
+            loops.push(boundary_loop);
+        }
+    }
+
+    Some(loops)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{polygon, rect_points};
+
+    #[test]
+    fn resolve_overlapping_polygons() {
+        let squares = [rect_points(0.0, 0.0, 2.0, 2.0), rect_points(1.0, 1.0, 3.0, 3.0)];
+
+        let union = Region2D::resolve_polygons(&squares, FillRule::NonZero).unwrap();
+        assert_eq!(union.edges().len(), 8);
+        assert!((union.area() - 7.0).abs() < 1e-9);
+        assert!(union.inside(na::Point2::new(1.5, 1.5)));
+        assert!(!union.inside(na::Point2::new(2.5, 0.5)));
+
+        // Nested polygons with the same orientation.
+        let nested = [rect_points(0.0, 0.0, 3.0, 3.0), rect_points(1.0, 1.0, 2.0, 2.0)];
+
+        let filled = Region2D::resolve_polygons(&nested, FillRule::NonZero).unwrap();
+        assert_eq!(filled.loops().len(), 1);
+        assert!((filled.area() - 9.0).abs() < 1e-9);
+
+        let ring = Region2D::resolve_polygons(&nested, FillRule::EvenOdd).unwrap();
+        assert_eq!(ring.loops().len(), 2);
+        assert!((ring.area() - 8.0).abs() < 1e-9);
+        assert!(!ring.inside(na::Point2::new(1.5, 1.5)));
+    }
+
+    #[test]
+    fn resolve_self_intersecting_polygon() {
+        // A pentagram, which has winding number two in the middle.
+        let star: Vec<_> = (0..5)
+            .map(|i| {
+                let a = std::f64::consts::TAU * (2 * i) as f64 / 5.0;
+                na::Point2::new(a.sin(), a.cos())
+            })
+            .collect();
+
+        let region = Region2D::resolve_polygons(std::slice::from_ref(&star), FillRule::NonZero).unwrap();
+        assert_eq!(region.edges().len(), 10);
+        assert!(region.inside(na::Point2::new(0.0, 0.0)));
+        assert!(region.inside(na::Point2::new(0.0, 0.9)));
+        assert!(!region.inside(na::Point2::new(0.3, 0.9)));
+
+        // Counterclockwise input is filled as well.
+        let reversed: Vec<_> = star.into_iter().rev().collect();
+        let region = Region2D::resolve_polygons(&[reversed], FillRule::NonZero).unwrap();
+        assert_eq!(region.edges().len(), 10);
+    }
+
+    #[test]
+    fn resolve_degenerate_polygon() {
+        // Repeated points, a collinear point and a spike.
+        let points = polygon(&[
+            (0.0, 0.0), (0.0, 0.0), (0.0, 1.0), (0.0, 2.0), (1.0, 2.0),
+            (1.0, 3.0), (1.0, 2.0), (2.0, 2.0), (2.0, 0.0),
+        ]);
+
+        let region = Region2D::resolve_polygons(&[points], FillRule::NonZero).unwrap();
+        assert_eq!(region.edges().len(), 4);
+        assert!((region.area() - 4.0).abs() < 1e-9);
+
+        let lines: Vec<_> = rect_points(0.0, 0.0, 2.0, 2.0).iter()
+            .zip(rect_points(0.0, 0.0, 2.0, 2.0).iter().cycle().skip(1))
+            .map(|(a, b)| Line::from_two_points(*a, *b))
+            .collect();
+
+        let region = Region2D::resolve_loops(&lines, &[vec![0, 1, 2, 3], vec![0, 1, 2, 3]], FillRule::EvenOdd)
+            .unwrap();
+        assert!(region.is_empty());
+    }
+}
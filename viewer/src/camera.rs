@@ -50,6 +50,9 @@ impl Trackball {
         if res.dragged_by(egui::PointerButton::Primary) {
             self.rotate(res.drag_delta().into());
         }
+        if res.dragged_by(egui::PointerButton::Secondary) {
+            self.pan(res.drag_delta().into());
+        }
         if res.hovered() {
             let zoom = res.ctx.input(|i| i.scroll_delta.y/3.0);
             self.zoom(zoom);
@@ -87,14 +90,6 @@ impl Trackball {
             + bitangent * (dd[1] * mult);
     }
 
-    pub fn target(&self) -> na::Point3<f32> {
-        self.target
-    }
-
-    pub fn look_at(&mut self, target: na::Point3<f32>) {
-        self.target = target;
-    }
-
     pub fn view(&self) -> na::Isometry3<f32> {
         let rot =
             na::UnitQuaternion::new(na::Vector3::x() * std::f32::consts::PI / -2.0)
@@ -110,19 +105,6 @@ impl Trackball {
             .to_projective()
     }
 
-    pub fn unproject_point(&self, point: na::Point2<f32>) -> (na::Point3<f32>, na::Vector3<f32>) {
-        let norm_x = 2.0 * point.coords.x / self.window_width - 1.0;
-        let norm_y = 2.0 * -point.coords.y / self.window_height + 1.0;
-        let p1 = na::Point3::new(norm_x, norm_y, -1.0);
-
-        let p2 = na::Point3::new(norm_x, norm_y, 1.0);
-
-        let p1 = self.transformation().inverse_transform_point(&p1);
-        let p2 = self.transformation().inverse_transform_point(&p2);
-
-        (p1, (p2 - p1).normalize())
-    }
-
     pub fn eye(&self) -> na::Point3<f32> {
         let px = self.target.x + self.dist * self.yaw.cos() * self.pitch.sin();
         let py = self.target.y + self.dist * self.yaw.sin() * self.pitch.sin();
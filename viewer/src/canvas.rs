@@ -129,10 +129,6 @@ impl<'a> CanvasUi<'a> {
         self.transform.scaling()
     }
 
-    fn max_point(&self) -> na::Point2<f64> {
-        self.origin() + self.size()
-    }
-
     fn edges(&self) -> [geometry::Line; 4] {
         let p0 = self.transform.inverse() * na::Point2::origin();
         let p1 = self.transform.inverse() * na::Point2::from(self.size());
@@ -197,8 +197,6 @@ impl Editor2D {
             });
 
         egui::CentralPanel::default().show_inside(ui, |ui| {
-            let size = ui.available_size();
-
             crate::canvas::Canvas::new("editor_canvas")
                 .size(ui.available_size())
                 .show(ui, |canvas| {
@@ -233,9 +231,10 @@ impl Editor2D {
                     let mut hover_point = None;
                     let mut selected = None;
                     for (i, p) in self.points.iter().enumerate() {
-                        if let Some(_) = canvas.mouse_pos()
+                        if canvas.mouse_pos()
                             .filter(|mp| (mp - p).norm() < 5.0 / canvas.scale_factor())
                             .filter(|_| self.tool == Tool::Pointer)
+                            .is_some()
                         {
                             hover_point = Some(i);
                             canvas.point(*p, egui::Color32::RED);
@@ -273,10 +272,11 @@ impl Editor2D {
                     for (i, l) in self.lines.iter().enumerate() {
                         let line = geometry::Line::from_two_points(self.points[l.p1], self.points[l.p2]);
 
-                        if let Some(proj) = canvas.mouse_pos()
+                        if canvas.mouse_pos()
                             .filter(|_| hover_point.is_none())
                             .map(|p| line.project_point(p))
                             .filter(|proj| proj.dist.abs() < 3.0 / canvas.scale_factor())
+                            .is_some()
                         {
                             canvas.line(line, (1.5, egui::Color32::RED));
                             if canvas.response().clicked() {
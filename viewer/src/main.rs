@@ -5,31 +5,37 @@ mod camera;
 mod wireframe_egui;
 mod canvas;
 mod editor_2d;
+mod render {
+    pub mod fill;
+}
 
 use kernel::mesh::Mesh;
 use kernel::geometry;
-use kernel::brep_1d::*;
-use kernel::brep_2d::*;
+use kernel::brep_2d::Region2D;
 
-use eframe::egui;
+use eframe::{egui, egui_wgpu};
 
-pub fn run(model: Mesh) {
-    let mut native_options = eframe::NativeOptions::default();
-    native_options.depth_buffer = 1;
-    native_options.vsync = false;
+pub fn run(model: Mesh, region: Region2D) {
+    let mut native_options = eframe::NativeOptions {
+        depth_buffer: 1,
+        vsync: false,
+        ..Default::default()
+    };
     native_options.wgpu_options.present_mode = wgpu::PresentMode::Immediate;
     //native_options.wgpu_options.depth_format = Some(wgpu::TextureFormat::Depth32Float);
-    eframe::run_native("CAM software", native_options, Box::new(move |cc| Box::new(App::new(cc, model))));
+    eframe::run_native("CAM software", native_options, Box::new(move |cc| Box::new(App::new(cc, model, region))))
+        .unwrap();
 }
 
 pub struct App {
     camera: camera::Trackball,
     object: std::sync::Arc<wireframe_egui::WireframeObject>,
+    fill: std::sync::Arc<render::fill::FillObject>,
     editor: editor_2d::Editor2D,
 }
 
 impl App {
-    fn new(cc: &eframe::CreationContext<'_>, model: Mesh) -> Self {
+    fn new(cc: &eframe::CreationContext<'_>, model: Mesh, region: Region2D) -> Self {
         let render_state = cc.wgpu_render_state.as_ref().unwrap();
         wireframe_egui::RenderContext::register(render_state);
         let renderer = render_state.renderer.read();
@@ -38,22 +44,57 @@ impl App {
             .hide_flat_edges()
             .build();
 
-        let camera = camera::Trackball::new(3.14 / 4.0);
+        // The region is filled in a plane below the model.
+        let frame = na::Isometry3::translation(0.0, 0.0, -2.0);
+        let fill = ctx.fill.object(render_state, &region.triangulate().to_mesh_in_plane(&frame), [0.2, 0.4, 0.7, 1.0]);
+
+        let camera = camera::Trackball::new(std::f32::consts::FRAC_PI_4);
 
         let editor = editor_2d::Editor2D::new();
 
         Self {
             camera,
             object: std::sync::Arc::new(object),
+            fill: std::sync::Arc::new(fill),
             editor,
         }
     }
+
+    fn show_3d(&mut self, ui: &mut egui::Ui) {
+        let (rect, response) = ui.allocate_exact_size(ui.available_size(), camera::Trackball::egui_sense());
+        self.camera.handle_egui_response(&response);
+        self.camera.update_size(rect.width(), rect.height());
+
+        let camera = self.camera;
+        let (prepare_object, prepare_fill) = (self.object.clone(), self.fill.clone());
+        let (object, fill) = (self.object.clone(), self.fill.clone());
+        let callback = egui_wgpu::CallbackFn::new()
+            .prepare(move |device, queue, _encoder, resources| {
+                let ctx = resources.get_mut::<wireframe_egui::RenderContext>().unwrap();
+                prepare_fill.prepare(ctx, queue, &camera);
+                prepare_object.prepare(ctx, device, queue, &camera);
+                Vec::new()
+            })
+            .paint(move |_info, render_pass, resources| {
+                let ctx = resources.get::<wireframe_egui::RenderContext>().unwrap();
+                fill.paint(ctx, render_pass);
+                object.paint(ctx, render_pass);
+            });
+
+        ui.painter().add(egui::PaintCallback {
+            rect,
+            callback: std::sync::Arc::new(callback),
+        });
+    }
 }
 
 impl eframe::App for App {
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         ctx.set_pixels_per_point(1.2);
         wireframe_egui::RenderContext::new_frame(frame);
+        egui::SidePanel::right("view_3d").default_width(400.0).show(ctx, |ui| {
+            self.show_3d(ui);
+        });
         egui::CentralPanel::default().show(ctx, |ui| {
             self.editor.show(ui);
 
@@ -191,29 +232,12 @@ impl eframe::App for App {
     }
 }
 
-fn render_1d_region(canvas: &canvas::CanvasUi, line: geometry::Line, region: &Region1D) {
-    canvas.line(line, (1.0, egui::Color32::DARK_GRAY));
-
-    for point in region.points() {
-        canvas.point(line.point(point.pos), egui::Color32::DARK_BLUE);
-    }
-
-    for cell in region.cells() {
-        let p1 = line.point(region.points()[cell[0]].pos);
-        let p2 = line.point(region.points()[cell[1]].pos);
-        canvas.line_segment(p1, p2, (1.5, egui::Color32::DARK_BLUE));
-    }
-
-    let proj = canvas.mouse_pos().map(|p| line.project_point(p));
-    if let Some(proj) = proj.filter(|p| p.dist.abs() <= 3.0 / canvas.scale_factor()) {
-        if region.inside(proj.pos) {
-            canvas.point(line.point(proj.pos), egui::Color32::RED);
-        } else {
-            canvas.point(line.point(proj.pos), egui::Color32::DARK_GRAY);
-        }
-    }
-}
-
 pub fn main() {
-    run(Mesh::cube(2.0));
+    // A square plate with a square hole.
+    let plate = Region2D::from_polygons(&[
+        vec![na::Point2::new(-3.0, -3.0), na::Point2::new(-3.0, 3.0), na::Point2::new(3.0, 3.0), na::Point2::new(3.0, -3.0)],
+        vec![na::Point2::new(-1.0, -1.0), na::Point2::new(1.0, -1.0), na::Point2::new(1.0, 1.0), na::Point2::new(-1.0, 1.0)],
+    ]).unwrap();
+
+    run(Mesh::cube(2.0), plate);
 }
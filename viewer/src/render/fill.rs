@@ -1,13 +1,44 @@
-use super::{RenderContext, RenderTarget};
+use crate::wireframe_egui::{FrameStorageHandle, RenderContext};
+use kernel::mesh::Mesh;
 use wgpu::util::DeviceExt;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+
+use eframe::egui_wgpu;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct Vertex {
+    position: [f32; 3],
+}
+
+// Numbers without implicit padding, see the GPU types in wireframe_egui.rs.
+unsafe impl bytemuck::Zeroable for Vertex {}
+unsafe impl bytemuck::Pod for Vertex {}
+
+impl Vertex {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 1] =
+        wgpu::vertex_attr_array![0 => Float32x3];
+
+    fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Vertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
 
 #[repr(C)]
-#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+#[derive(Debug, Copy, Clone)]
 struct Uniforms {
-    color: [f32; 4]
+    matrix: [[f32; 4]; 4],
+    color: [f32; 4],
 }
 
+unsafe impl bytemuck::Zeroable for Uniforms {}
+unsafe impl bytemuck::Pod for Uniforms {}
+
+/// Draws the triangles of a mesh in one color, for example the triangulation of a region.
 #[derive(Clone)]
 pub struct FillShader {
     inner: Arc<Inner>,
@@ -20,8 +51,8 @@ struct Inner {
 }
 
 impl FillShader {
-    pub(super) fn new(ctx: &RenderContext) -> Self {
-        let uniforms = ctx.device.create_buffer_init(
+    pub fn register(state: &egui_wgpu::RenderState) -> Self {
+        let uniforms = state.device.create_buffer_init(
             &wgpu::util::BufferInitDescriptor {
                 label: Some("fill uniform buffer"),
                 contents: &[0; std::mem::size_of::<Uniforms>()],
@@ -29,12 +60,12 @@ impl FillShader {
             }
         );
 
-        let uniform_bind_group_layout = 
-            ctx.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        let uniform_bind_group_layout =
+            state.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 entries: &[
                     wgpu::BindGroupLayoutEntry {
                         binding: 0,
-                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
                         ty: wgpu::BindingType::Buffer {
                             ty: wgpu::BufferBindingType::Uniform,
                             has_dynamic_offset: false,
@@ -45,8 +76,8 @@ impl FillShader {
                 ],
                 label: Some("fill uniform bind group layout"),
             });
-        
-        let uniforms_bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+
+        let uniforms_bind_group = state.device.create_bind_group(&wgpu::BindGroupDescriptor {
             layout: &uniform_bind_group_layout,
             entries: &[
                 wgpu::BindGroupEntry {
@@ -57,34 +88,32 @@ impl FillShader {
             label: Some("fill uniform bind group"),
         });
 
-        let shader = ctx.device.create_shader_module(wgpu::include_wgsl!("shaders/fill.wgsl"));
+        let shader = state.device.create_shader_module(wgpu::include_wgsl!("shaders/fill.wgsl"));
 
         let pipeline_layout =
-            ctx.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            state.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 label: Some("Render Pipeline Layout"),
                 bind_group_layouts: &[&uniform_bind_group_layout],
                 push_constant_ranges: &[],
             });
 
-        let pipeline = ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        let pipeline = state.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("fill render pipeline"),
             layout: Some(&pipeline_layout),
             vertex: wgpu::VertexState {
                 module: &shader,
                 entry_point: "vs_main",
-                buffers: &[],
+                buffers: &[
+                    Vertex::desc(),
+                ],
             },
             fragment: Some(wgpu::FragmentState {
                 module: &shader,
                 entry_point: "fs_main",
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: ctx.config.format,
-                    blend: Some(wgpu::BlendState::REPLACE),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
+                targets: &[Some(state.target_format.into())],
             }),
             primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                topology: wgpu::PrimitiveTopology::TriangleList,
                 strip_index_format: None,
                 front_face: wgpu::FrontFace::Ccw,
                 cull_mode: None,
@@ -99,11 +128,7 @@ impl FillShader {
                 stencil: wgpu::StencilState::default(),
                 bias: wgpu::DepthBiasState::default(),
             }),
-            multisample: wgpu::MultisampleState {
-                count: ctx.samples,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
+            multisample: wgpu::MultisampleState::default(),
             multiview: None,
         });
 
@@ -116,40 +141,53 @@ impl FillShader {
         }
     }
 
-    pub fn render(&self, target: &mut RenderTarget) {
+    pub fn object(&self, ctx: &egui_wgpu::RenderState, geometry: &Mesh, color: [f32; 4]) -> FillObject {
+        let shape: Vec<Vertex> = geometry.faces.iter()
+            .flatten()
+            .map(|v| Vertex { position: geometry.vertices[*v].coords.into() })
+            .collect();
+
+        let vertices = ctx.device.create_buffer_init(
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("fill vertex buffer"),
+                contents: bytemuck::cast_slice(&shape),
+                usage: wgpu::BufferUsages::VERTEX,
+            }
+        );
+
+        FillObject {
+            vertices: Arc::new(vertices),
+            vertex_count: shape.len() as u32,
+            color,
+            renderer: self.clone(),
+            frame_store_idx: Mutex::new(FrameStorageHandle::empty()),
+        }
+    }
+}
+
+pub struct FillObject {
+    vertices: Arc<wgpu::Buffer>,
+    vertex_count: u32,
+    color: [f32; 4],
+    renderer: FillShader,
+    frame_store_idx: Mutex<FrameStorageHandle<wgpu::Buffer>>,
+}
+
+impl FillObject {
+    pub fn prepare(&self, ctx: &mut RenderContext, queue: &wgpu::Queue, camera: &crate::camera::Trackball) {
         let uniforms = Uniforms {
-            color: [0.05, 0.05, 0.05, 1.0],
+            matrix: (*camera.transformation().matrix()).into(),
+            color: self.color,
         };
 
-        target.ctx.queue.write_buffer(&self.inner.uniforms, 0, bytemuck::cast_slice(&[uniforms]));
-        
-        let mut render_pass = target.encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: Some("wireframe render pass"),
-            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: target.view,
-                resolve_target: target.resolve_target,
-                ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(wgpu::Color {
-                        r: 0.0,
-                        g: 0.0,
-                        b: 0.0,
-                        a: 1.0,
-                    }),
-                    store: true,
-                },
-            })],
-            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                view: &target.ctx.depth,
-                depth_ops: Some(wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(1.0),
-                    store: true,
-                }),
-                stencil_ops: None,
-            }),
-        });
+        queue.write_buffer(&self.renderer.inner.uniforms, 0, bytemuck::cast_slice(&[uniforms]));
+        *self.frame_store_idx.lock().unwrap() = ctx.frame.store(self.vertices.clone());
+    }
 
-        render_pass.set_pipeline(&self.inner.pipeline);
-        render_pass.set_bind_group(0, &self.inner.uniforms_bind_group, &[]);
-        render_pass.draw(0..4, 0..1);
+    pub fn paint<'a, 'c>(&'a self, ctx: &'c RenderContext, render_pass: &'a mut wgpu::RenderPass<'c>) {
+        render_pass.set_pipeline(&ctx.fill.inner.pipeline);
+        render_pass.set_bind_group(0, &ctx.fill.inner.uniforms_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, ctx.frame.get(*self.frame_store_idx.lock().unwrap()).unwrap().slice(..));
+        render_pass.draw(0..self.vertex_count, 0..1);
     }
 }
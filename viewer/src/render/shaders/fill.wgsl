@@ -1,27 +1,25 @@
 // Vertex shader
 
+struct FillUniform {
+    matrix: mat4x4<f32>,
+    color: vec4<f32>,
+};
+@group(0) @binding(0)
+var<uniform> fill: FillUniform;
+
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
 };
 
 @vertex
 fn vs_main(
-    @builtin(vertex_index) in_vertex_index: u32,
+    @location(0) position: vec3<f32>,
 ) -> VertexOutput {
     var out: VertexOutput;
-    let x = f32(in_vertex_index & 1u) * 2.0 - 1.0;
-    let y = f32(in_vertex_index / 2u) * 2.0 - 1.0;
-    out.clip_position = vec4<f32>(x, y, 0.0, 1.0);
+    out.clip_position = fill.matrix * vec4<f32>(position, 1.0);
     return out;
 }
 
-
-struct FillUniform {
-    color: vec4<f32>,
-};
-@group(0) @binding(0)
-var<uniform> fill: FillUniform;
-
 // Fragment shader
 
 @fragment
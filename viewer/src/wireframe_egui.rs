@@ -4,10 +4,9 @@ use std::sync::{Arc, Mutex};
 use std::collections::HashMap;
 
 use eframe::egui_wgpu;
-use eframe::egui::epaint;
 
 #[repr(C)]
-#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+#[derive(Copy, Clone, Debug)]
 struct Vertex {
     position: [f32; 3],
     //color: [f32; 3],
@@ -15,6 +14,11 @@ struct Vertex {
     normal: [f32; 3],
 }
 
+// The GPU types only hold numbers without implicit padding. Pod is implemented by hand, as the
+// padding checks generated by its derive are reported as dead code.
+unsafe impl bytemuck::Zeroable for Vertex {}
+unsafe impl bytemuck::Pod for Vertex {}
+
 impl Vertex {
     const ATTRIBUTES: [wgpu::VertexAttribute; 3] =
         wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3, 2 => Float32x3];
@@ -29,7 +33,7 @@ impl Vertex {
 }
 
 #[repr(C)]
-#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+#[derive(Debug, Copy, Clone)]
 struct Uniforms {
     matrix: [[f32; 4]; 4],
     //view_matrix: [[f32; 3]; 3],
@@ -41,9 +45,13 @@ struct Uniforms {
     __align: [u8; 4],
 }
 
+unsafe impl bytemuck::Zeroable for Uniforms {}
+unsafe impl bytemuck::Pod for Uniforms {}
+
 pub struct RenderContext {
     pub frame: FrameStorage,
     pub wireframe: Wireframe,
+    pub fill: crate::render::fill::FillShader,
 }
 
 impl RenderContext {
@@ -53,6 +61,7 @@ impl RenderContext {
                 objects: Vec::new(),
             },
             wireframe: Wireframe::register(state),
+            fill: crate::render::fill::FillShader::register(state),
         };
         
         state.renderer.write()
@@ -62,7 +71,7 @@ impl RenderContext {
     pub fn new_frame(frame: &eframe::Frame) {
         let mut renderer = frame.wgpu_render_state().unwrap()
             .renderer.write();
-        let mut ctx = renderer.paint_callback_resources.get_mut::<Self>()
+        let ctx = renderer.paint_callback_resources.get_mut::<Self>()
             .unwrap();
         ctx.frame.objects = Vec::new();
     }
@@ -84,10 +93,7 @@ pub struct FrameStorageHandle<T> {
 
 impl<T> Clone for FrameStorageHandle<T> {
     fn clone(&self) -> Self {
-        Self {
-            i: self.i,
-            _marker: std::marker::PhantomData,
-        }
+        *self
     }
 }
 
@@ -239,11 +245,8 @@ pub struct WireframeObject {
 }
 
 impl WireframeObject {
-    pub fn prepare(&self, ctx: &mut RenderContext, device: &wgpu::Device, queue: &wgpu::Queue, camera: &crate::camera::Trackball) {
+    pub fn prepare(&self, ctx: &mut RenderContext, _device: &wgpu::Device, queue: &wgpu::Queue, camera: &crate::camera::Trackball) {
         let light_dir = camera.view().inverse() * na::Vector3::new(6.0, 6.0, 10.0);
-        let x_axis = camera.view() * na::Vector::x();
-        let y_axis = camera.view() * na::Vector::y();
-        let z_axis = camera.view() * na::Vector::z();
         let uniforms = Uniforms {
             matrix: (*camera.transformation().matrix()).into(),
             //view_matrix: (*camera.view().rotation.to_rotation_matrix().matrix()).into(),